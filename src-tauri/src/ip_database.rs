@@ -0,0 +1,372 @@
+use crate::ip_location::IpLocationResult;
+use chrono::DateTime;
+use maxminddb::{MaxMindDBError, Reader};
+use serde_json::Value;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr};
+
+// ip2region xdb 文件布局：256 字节头部 + 256*256 向量索引 + 数据区 + 14 字节的段索引
+const XDB_HEADER_SIZE: usize = 256;
+const XDB_VECTOR_COLS: usize = 256;
+const XDB_VECTOR_ENTRY_SIZE: usize = 8;
+const XDB_SEGMENT_SIZE: usize = 14;
+const XDB_STRUCTURE_VERSION: u16 = 2;
+
+pub(crate) enum LocalDatabase {
+    Mmdb {
+        file_path: String,
+        reader: Reader<Vec<u8>>,
+    },
+    Xdb {
+        file_path: String,
+        data: XdbData,
+    },
+}
+
+pub(crate) struct XdbData {
+    buffer: Vec<u8>,
+    created_at: u32,
+    start_index: usize,
+    end_index: usize,
+}
+
+pub(crate) struct LocalDatabaseSummary {
+    pub kind: &'static str,
+    pub file_path: String,
+    pub database_type: String,
+    pub build_date: String,
+    pub record_count: Option<u64>,
+    // mmdb 搜索树的节点数，不等于记录数
+    pub node_count: Option<u64>,
+    pub supports_ipv4: bool,
+    pub supports_ipv6: bool,
+}
+
+impl LocalDatabase {
+    pub(crate) fn open_mmdb(file_path: &str) -> Result<Self, String> {
+        let reader = Reader::open_readfile(file_path)
+            .map_err(|err| format!("无法打开 mmdb 数据库 {}: {}", file_path, err))?;
+        Ok(LocalDatabase::Mmdb {
+            file_path: file_path.to_string(),
+            reader,
+        })
+    }
+
+    pub(crate) fn open_xdb(file_path: &str) -> Result<Self, String> {
+        let buffer = fs::read(file_path)
+            .map_err(|err| format!("无法读取 xdb 数据库 {}: {}", file_path, err))?;
+        let data = XdbData::from_buffer(buffer)?;
+        Ok(LocalDatabase::Xdb {
+            file_path: file_path.to_string(),
+            data,
+        })
+    }
+
+    pub(crate) fn file_path(&self) -> &str {
+        match self {
+            LocalDatabase::Mmdb { file_path, .. } | LocalDatabase::Xdb { file_path, .. } => {
+                file_path
+            }
+        }
+    }
+
+    pub(crate) fn summary(&self) -> LocalDatabaseSummary {
+        match self {
+            LocalDatabase::Mmdb { file_path, reader } => {
+                let metadata = &reader.metadata;
+                LocalDatabaseSummary {
+                    kind: "mmdb",
+                    file_path: file_path.clone(),
+                    database_type: metadata.database_type.clone(),
+                    build_date: format_epoch(metadata.build_epoch as i64),
+                    // mmdb 元数据中没有记录数
+                    record_count: None,
+                    node_count: Some(u64::from(metadata.node_count)),
+                    supports_ipv4: true,
+                    supports_ipv6: metadata.ip_version == 6,
+                }
+            }
+            LocalDatabase::Xdb { file_path, data } => LocalDatabaseSummary {
+                kind: "xdb",
+                file_path: file_path.clone(),
+                database_type: "ip2region".to_string(),
+                build_date: format_epoch(i64::from(data.created_at)),
+                record_count: Some(data.segment_count()),
+                node_count: None,
+                supports_ipv4: true,
+                supports_ipv6: false,
+            },
+        }
+    }
+
+    pub(crate) fn lookup(&self, ip: IpAddr) -> Result<IpLocationResult, String> {
+        match self {
+            LocalDatabase::Mmdb { reader, .. } => {
+                let record: Value = reader.lookup(ip).map_err(|err| match err {
+                    MaxMindDBError::AddressNotFoundError(_) => {
+                        "数据库中没有该 IP 的记录".to_string()
+                    }
+                    other => format!("查询 mmdb 数据库失败: {}", other),
+                })?;
+                Ok(parse_mmdb_record(&record))
+            }
+            LocalDatabase::Xdb { data, .. } => {
+                let IpAddr::V4(ipv4) = ip else {
+                    return Err("ip2region xdb 数据库仅支持 IPv4".to_string());
+                };
+                let region = data
+                    .search(ipv4)?
+                    .ok_or_else(|| "数据库中没有该 IP 的记录".to_string())?;
+                Ok(parse_xdb_region(&region))
+            }
+        }
+    }
+}
+
+impl XdbData {
+    fn from_buffer(buffer: Vec<u8>) -> Result<Self, String> {
+        let vector_end =
+            XDB_HEADER_SIZE + XDB_VECTOR_COLS * XDB_VECTOR_COLS * XDB_VECTOR_ENTRY_SIZE;
+        if buffer.len() < vector_end {
+            return Err("xdb 文件长度不足，可能已损坏".to_string());
+        }
+
+        let version = read_u16(&buffer, 0)?;
+        if version != XDB_STRUCTURE_VERSION {
+            return Err(format!("不支持的 xdb 结构版本: {}", version));
+        }
+
+        let created_at = read_u32(&buffer, 4)?;
+        let start_index = read_u32(&buffer, 8)? as usize;
+        let end_index = read_u32(&buffer, 12)? as usize;
+        if start_index > end_index || end_index + XDB_SEGMENT_SIZE > buffer.len() {
+            return Err("xdb 段索引位置无效".to_string());
+        }
+
+        Ok(XdbData {
+            buffer,
+            created_at,
+            start_index,
+            end_index,
+        })
+    }
+
+    fn segment_count(&self) -> u64 {
+        ((self.end_index - self.start_index) / XDB_SEGMENT_SIZE + 1) as u64
+    }
+
+    // 先用前两个字节定位向量索引，再在对应的段索引区间内二分查找
+    fn search(&self, ip: Ipv4Addr) -> Result<Option<String>, String> {
+        let octets = ip.octets();
+        let value = u32::from(ip);
+        let vector_offset = XDB_HEADER_SIZE
+            + (octets[0] as usize * XDB_VECTOR_COLS + octets[1] as usize) * XDB_VECTOR_ENTRY_SIZE;
+        let start_ptr = read_u32(&self.buffer, vector_offset)? as usize;
+        let end_ptr = read_u32(&self.buffer, vector_offset + 4)? as usize;
+        if start_ptr == 0 && end_ptr == 0 {
+            return Ok(None);
+        }
+        if end_ptr < start_ptr {
+            return Err("xdb 向量索引无效".to_string());
+        }
+
+        let mut low = 0usize;
+        let mut high = (end_ptr - start_ptr) / XDB_SEGMENT_SIZE;
+        while low <= high {
+            let middle = (low + high) / 2;
+            let offset = start_ptr + middle * XDB_SEGMENT_SIZE;
+            let segment_start = read_u32(&self.buffer, offset)?;
+            let segment_end = read_u32(&self.buffer, offset + 4)?;
+
+            if value < segment_start {
+                if middle == 0 {
+                    break;
+                }
+                high = middle - 1;
+            } else if value > segment_end {
+                low = middle + 1;
+            } else {
+                let data_len = read_u16(&self.buffer, offset + 8)? as usize;
+                let data_ptr = read_u32(&self.buffer, offset + 10)? as usize;
+                let bytes = self
+                    .buffer
+                    .get(data_ptr..data_ptr + data_len)
+                    .ok_or_else(|| "xdb 数据区越界".to_string())?;
+                return Ok(Some(String::from_utf8_lossy(bytes).into_owned()));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+fn read_u16(buffer: &[u8], offset: usize) -> Result<u16, String> {
+    buffer
+        .get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| "xdb 文件读取越界".to_string())
+}
+
+fn read_u32(buffer: &[u8], offset: usize) -> Result<u32, String> {
+    buffer
+        .get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or_else(|| "xdb 文件读取越界".to_string())
+}
+
+fn format_epoch(seconds: i64) -> String {
+    DateTime::from_timestamp(seconds, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "未知".to_string())
+}
+
+// ip2region 区域格式为 国家|区域|省份|城市|ISP（新版去掉了区域），0 表示未知
+fn parse_xdb_region(region: &str) -> IpLocationResult {
+    let fields: Vec<&str> = region.split('|').collect();
+    let pick = |index: usize| match fields.get(index) {
+        Some(value) if !value.is_empty() && *value != "0" => value.to_string(),
+        _ => "未知".to_string(),
+    };
+
+    let (country, province, city, isp) = if fields.len() >= 5 {
+        (pick(0), pick(2), pick(3), pick(4))
+    } else {
+        (pick(0), pick(1), pick(2), pick(3))
+    };
+
+    IpLocationResult {
+        country,
+        region: province,
+        city,
+        isp,
+        raw_info: region.to_string(),
+    }
+}
+
+// 兼容 GeoIP2/GeoLite2 的 City、Country、ASN、ISP 库，以及字段为纯字符串的第三方 mmdb
+fn parse_mmdb_record(record: &Value) -> IpLocationResult {
+    let country = localized_name(&record["country"]);
+    let region = localized_name(&record["subdivisions"][0]);
+    let city = localized_name(&record["city"]);
+    let isp = [
+        "isp",
+        "organization",
+        "autonomous_system_organization",
+        "asn",
+    ]
+    .iter()
+    .find_map(|key| record[*key].as_str())
+    .unwrap_or("未知")
+    .to_string();
+
+    IpLocationResult {
+        country,
+        region,
+        city,
+        isp,
+        raw_info: record.to_string(),
+    }
+}
+
+fn localized_name(value: &Value) -> String {
+    if let Some(name) = value.as_str() {
+        return name.to_string();
+    }
+    ["zh-CN", "en"]
+        .iter()
+        .find_map(|lang| value["names"][*lang].as_str())
+        .unwrap_or("未知")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_xdb(segments: &[(Ipv4Addr, Ipv4Addr, &str)]) -> Vec<u8> {
+        let vector_end =
+            XDB_HEADER_SIZE + XDB_VECTOR_COLS * XDB_VECTOR_COLS * XDB_VECTOR_ENTRY_SIZE;
+        let mut buffer = vec![0u8; vector_end];
+
+        let mut data_ptrs = Vec::new();
+        for (_, _, region) in segments {
+            data_ptrs.push((buffer.len() as u32, region.len() as u16));
+            buffer.extend_from_slice(region.as_bytes());
+        }
+
+        let start_index = buffer.len();
+        for ((start, end, _), (data_ptr, data_len)) in segments.iter().zip(&data_ptrs) {
+            let segment_offset = buffer.len() as u32;
+            buffer.extend_from_slice(&u32::from(*start).to_le_bytes());
+            buffer.extend_from_slice(&u32::from(*end).to_le_bytes());
+            buffer.extend_from_slice(&data_len.to_le_bytes());
+            buffer.extend_from_slice(&data_ptr.to_le_bytes());
+
+            let octets = start.octets();
+            let vector_offset = XDB_HEADER_SIZE
+                + (octets[0] as usize * XDB_VECTOR_COLS + octets[1] as usize)
+                    * XDB_VECTOR_ENTRY_SIZE;
+            if buffer[vector_offset..vector_offset + 4] == [0, 0, 0, 0] {
+                buffer[vector_offset..vector_offset + 4]
+                    .copy_from_slice(&segment_offset.to_le_bytes());
+            }
+            buffer[vector_offset + 4..vector_offset + 8]
+                .copy_from_slice(&segment_offset.to_le_bytes());
+        }
+        let end_index = buffer.len() - XDB_SEGMENT_SIZE;
+
+        buffer[0..2].copy_from_slice(&XDB_STRUCTURE_VERSION.to_le_bytes());
+        buffer[4..8].copy_from_slice(&1_700_000_000u32.to_le_bytes());
+        buffer[8..12].copy_from_slice(&(start_index as u32).to_le_bytes());
+        buffer[12..16].copy_from_slice(&(end_index as u32).to_le_bytes());
+        buffer
+    }
+
+    #[test]
+    fn searches_xdb_segments() {
+        let buffer = build_xdb(&[
+            (
+                Ipv4Addr::new(1, 2, 0, 0),
+                Ipv4Addr::new(1, 2, 3, 255),
+                "中国|0|广东省|深圳市|电信",
+            ),
+            (
+                Ipv4Addr::new(1, 2, 4, 0),
+                Ipv4Addr::new(1, 2, 255, 255),
+                "中国|浙江省|杭州市|联通",
+            ),
+        ]);
+        let data = XdbData::from_buffer(buffer).expect("should load");
+        assert_eq!(data.segment_count(), 2);
+
+        let region = data
+            .search(Ipv4Addr::new(1, 2, 3, 4))
+            .unwrap()
+            .expect("should match");
+        let location = parse_xdb_region(&region);
+        assert_eq!(location.region, "广东省");
+        assert_eq!(location.isp, "电信");
+
+        let region = data
+            .search(Ipv4Addr::new(1, 2, 200, 1))
+            .unwrap()
+            .expect("should match");
+        assert_eq!(parse_xdb_region(&region).city, "杭州市");
+
+        assert!(data.search(Ipv4Addr::new(8, 8, 8, 8)).unwrap().is_none());
+    }
+
+    #[test]
+    fn reads_geoip2_city_record() {
+        let record = serde_json::json!({
+            "country": { "names": { "en": "Japan", "zh-CN": "日本" } },
+            "subdivisions": [{ "names": { "en": "Tokyo" } }],
+            "city": { "names": { "en": "Tokyo" } },
+            "autonomous_system_organization": "Example ISP"
+        });
+        let location = parse_mmdb_record(&record);
+        assert_eq!(location.country, "日本");
+        assert_eq!(location.region, "Tokyo");
+        assert_eq!(location.isp, "Example ISP");
+    }
+}
@@ -1,7 +1,15 @@
-use serde::Serialize;
+use crate::ip_database::LocalDatabase;
+use crate::ip_location_provider::{
+    current_config, flush_location_cache, lookup_with_providers, parse_mir6_result, MIR6_API_URL,
+};
+use crate::ip_registry::{lookup_ipv4, lookup_ipv6};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use tokio::net::lookup_host;
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct IpLocationResult {
     pub country: String,
@@ -17,82 +25,340 @@ pub struct LocationInfo {
     pub ip: String,
     pub location: IpLocationResult,
     pub is_ipv4: bool,
+    pub source: String,
+    pub cached: bool,
+    pub hostname: Option<String>,
+    pub resolved: Vec<ResolvedLocation>,
+}
+
+// 输入为域名时，每条 A/AAAA 记录的查询结果
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedLocation {
+    pub ip: String,
+    pub is_ipv4: bool,
+    pub location: Option<IpLocationResult>,
+    pub error: Option<String>,
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DatabaseInfo {
-    pub api_endpoint: String,
+    pub backend: String,
+    pub api_endpoint: Option<String>,
+    pub providers: Vec<String>,
+    pub file_path: Option<String>,
+    pub database_type: Option<String>,
+    pub build_date: Option<String>,
+    pub record_count: Option<u64>,
+    pub node_count: Option<u64>,
     pub supports_ipv4: bool,
     pub supports_ipv6: bool,
 }
 
-const IP_API_URL: &str = "https://api.mir6.com/api/ip";
+// 查询来源：在线接口，或磁盘上的 MaxMind mmdb / ip2region xdb 离线库
+#[derive(Deserialize, Clone)]
+#[serde(tag = "source", rename_all = "camelCase")]
+pub enum LocationBackend {
+    Online,
+    Mmdb { file_path: String },
+    Xdb { file_path: String },
+}
+
+enum LookupTarget {
+    Address(IpAddr),
+    Host(String),
+}
+
+#[derive(Default)]
+struct DatabaseState {
+    local: Option<LocalDatabase>,
+    use_local: bool,
+}
+
+fn database_state() -> &'static Mutex<DatabaseState> {
+    static STATE: OnceLock<Mutex<DatabaseState>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(DatabaseState::default()))
+}
 
 #[tauri::command]
-pub async fn lookup_ip_location(ip: String) -> Result<LocationInfo, String> {
-    let url = format!("{}?ip={}&type=json", IP_API_URL, ip);
+pub async fn lookup_ip_location(
+    ip: String,
+    backend: Option<LocationBackend>,
+) -> Result<LocationInfo, String> {
+    let result = match parse_lookup_target(&ip)? {
+        LookupTarget::Address(addr) => lookup_address(addr, backend).await,
+        LookupTarget::Host(host) => lookup_hostname(host, backend).await,
+    };
+    flush_location_cache()?;
+    result
+}
 
-    // 判断 IP 类型
-    let is_ipv4 = ip.contains('.');
+// 不写缓存文件的查询入口，批量查询和 NAT 生成在结束后统一调用 flush_location_cache
+pub(crate) async fn resolve_location(
+    ip: String,
+    backend: Option<LocationBackend>,
+) -> Result<LocationInfo, String> {
+    match parse_lookup_target(&ip)? {
+        LookupTarget::Address(addr) => lookup_address(addr, backend).await,
+        LookupTarget::Host(_) => Err(format!("无效的 IP 地址: {}", ip.trim())),
+    }
+}
 
-    match reqwest::get(&url).await {
-        Ok(response) => match response.json::<Value>().await {
-            Ok(json_data) => match parse_json_result(&json_data) {
-                Ok(location) => Ok(LocationInfo {
-                    ip: ip.clone(),
-                    location,
-                    is_ipv4,
-                }),
-                Err(e) => Err(format!("解析 JSON 数据失败: {}", e)),
-            },
-            Err(e) => Err(format!("获取 JSON 响应失败: {}", e)),
-        },
-        Err(e) => Err(format!("请求失败: {}", e)),
+async fn lookup_address(
+    addr: IpAddr,
+    backend: Option<LocationBackend>,
+) -> Result<LocationInfo, String> {
+    ensure_public_address(addr)?;
+    match backend.unwrap_or(LocationBackend::Online) {
+        LocationBackend::Online => lookup_online(addr).await,
+        local => lookup_local(addr, &local),
     }
 }
 
-fn parse_json_result(json: &Value) -> Result<IpLocationResult, String> {
-    // 提取数据字段
-    let country = json["data"]["country"]
-        .as_str()
-        .unwrap_or("未知")
-        .to_string();
+// 域名解析出的每个地址分别查询，第一个成功的结果作为主结果返回
+async fn lookup_hostname(
+    host: String,
+    backend: Option<LocationBackend>,
+) -> Result<LocationInfo, String> {
+    let mut addresses: Vec<IpAddr> = Vec::new();
+    let resolved_addrs = lookup_host((host.as_str(), 0))
+        .await
+        .map_err(|err| format!("解析域名 {} 失败: {}", host, err))?;
+    for socket_addr in resolved_addrs {
+        let addr = normalize_ip(socket_addr.ip());
+        if !addresses.contains(&addr) {
+            addresses.push(addr);
+        }
+    }
+    if addresses.is_empty() {
+        return Err(format!("域名 {} 没有 A/AAAA 记录", host));
+    }
 
-    let region = json["data"]["province"]
-        .as_str()
-        .unwrap_or("未知")
-        .to_string();
+    let mut primary: Option<LocationInfo> = None;
+    let mut resolved = Vec::new();
+    for addr in addresses {
+        match lookup_address(addr, backend.clone()).await {
+            Ok(info) => {
+                resolved.push(ResolvedLocation {
+                    ip: info.ip.clone(),
+                    is_ipv4: info.is_ipv4,
+                    location: Some(info.location.clone()),
+                    error: None,
+                });
+                if primary.is_none() {
+                    primary = Some(info);
+                }
+            }
+            Err(err) => resolved.push(ResolvedLocation {
+                ip: addr.to_string(),
+                is_ipv4: addr.is_ipv4(),
+                location: None,
+                error: Some(err),
+            }),
+        }
+    }
 
-    let city = json["data"]["city"].as_str().unwrap_or("未知").to_string();
+    let Some(mut info) = primary else {
+        let errors: Vec<String> = resolved
+            .iter()
+            .map(|item| format!("{}: {}", item.ip, item.error.clone().unwrap_or_default()))
+            .collect();
+        return Err(format!(
+            "域名 {} 的地址均查询失败: {}",
+            host,
+            errors.join("; ")
+        ));
+    };
+    info.hostname = Some(host);
+    info.resolved = resolved;
+    Ok(info)
+}
+
+fn parse_lookup_target(input: &str) -> Result<LookupTarget, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err("请输入 IP 地址或域名".to_string());
+    }
 
-    let isp = json["data"]["isp"].as_str().unwrap_or("未知").to_string();
+    // 兼容 [2001:db8::1] 写法，并去掉 fe80::1%eth0 这类接口标识
+    let candidate = trimmed
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(trimmed);
+    let candidate = candidate.split('%').next().unwrap_or(candidate);
+    if let Ok(addr) = candidate.parse::<IpAddr>() {
+        return Ok(LookupTarget::Address(normalize_ip(addr)));
+    }
 
-    // 如果城市未知且省份是直辖市，设为相同
-    let final_city = if city == "未知"
-        && (region.ends_with("市") || region.ends_with("县") || region.ends_with("区"))
-    {
-        region.clone()
+    let host = trimmed.trim_end_matches('.').to_lowercase();
+    let valid_host = !host.is_empty()
+        && host.len() <= 253
+        && host.chars().any(|c| c.is_ascii_alphabetic())
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if valid_host {
+        Ok(LookupTarget::Host(host))
     } else {
-        city
+        Err(format!("无效的 IP 地址或域名: {}", trimmed))
+    }
+}
+
+// IPv4 映射地址（::ffff:a.b.c.d）按 IPv4 处理
+pub(crate) fn normalize_ip(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6
+            .to_ipv4_mapped()
+            .map(IpAddr::V4)
+            .unwrap_or(IpAddr::V6(v6)),
+        v4 => v4,
+    }
+}
+
+// 私有、保留等不可全球路由的地址在本地给出说明，不再请求接口
+pub(crate) fn ensure_public_address(addr: IpAddr) -> Result<(), String> {
+    let special = match addr {
+        IpAddr::V4(v4) => lookup_ipv4(u32::from(v4)),
+        IpAddr::V6(v6) => lookup_ipv6(u128::from(v6)),
     };
 
-    Ok(IpLocationResult {
-        country,
-        region,
-        city: final_city,
-        isp,
-        raw_info: json.to_string(),
+    match special {
+        Some(info) if info.globally_reachable == Some(false) || addr.is_multicast() => {
+            Err(format!(
+                "{} 属于 {}（{}，{}），不是公网地址，没有归属地信息",
+                addr, info.block, info.name, info.rfc
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn open_location_database(backend: LocationBackend) -> Result<DatabaseInfo, String> {
+    let mut state = database_state()
+        .lock()
+        .map_err(|_| "数据库状态锁定失败".to_string())?;
+    match backend {
+        LocationBackend::Online => state.use_local = false,
+        local => {
+            state.local = Some(open_local(&local)?);
+            state.use_local = true;
+        }
+    }
+    Ok(describe_state(&state))
+}
+
+fn lookup_local(addr: IpAddr, backend: &LocationBackend) -> Result<LocationInfo, String> {
+    let mut state = database_state()
+        .lock()
+        .map_err(|_| "数据库状态锁定失败".to_string())?;
+
+    // 同一文件只加载一次，切换文件或类型时重新打开
+    let reusable = match (&state.local, backend) {
+        (Some(db @ LocalDatabase::Mmdb { .. }), LocationBackend::Mmdb { file_path })
+        | (Some(db @ LocalDatabase::Xdb { .. }), LocationBackend::Xdb { file_path }) => {
+            db.file_path() == file_path
+        }
+        _ => false,
+    };
+    if !reusable {
+        state.local = Some(open_local(backend)?);
+    }
+    state.use_local = true;
+
+    let database = state
+        .local
+        .as_ref()
+        .ok_or_else(|| "离线数据库未加载".to_string())?;
+    let location = database.lookup(addr)?;
+
+    Ok(LocationInfo {
+        ip: addr.to_string(),
+        location,
+        is_ipv4: addr.is_ipv4(),
+        source: database.summary().kind.to_string(),
+        cached: false,
+        hostname: None,
+        resolved: Vec::new(),
+    })
+}
+
+fn open_local(backend: &LocationBackend) -> Result<LocalDatabase, String> {
+    match backend {
+        LocationBackend::Mmdb { file_path } => LocalDatabase::open_mmdb(file_path),
+        LocationBackend::Xdb { file_path } => LocalDatabase::open_xdb(file_path),
+        LocationBackend::Online => Err("在线接口不需要加载数据库".to_string()),
+    }
+}
+
+fn describe_state(state: &DatabaseState) -> DatabaseInfo {
+    let order = current_config()
+        .map(|config| config.order)
+        .unwrap_or_default();
+    match (&state.local, state.use_local) {
+        (Some(database), true) => {
+            let summary = database.summary();
+            DatabaseInfo {
+                backend: summary.kind.to_string(),
+                api_endpoint: None,
+                providers: Vec::new(),
+                file_path: Some(summary.file_path),
+                database_type: Some(summary.database_type),
+                build_date: Some(summary.build_date),
+                record_count: summary.record_count,
+                node_count: summary.node_count,
+                supports_ipv4: summary.supports_ipv4,
+                supports_ipv6: summary.supports_ipv6,
+            }
+        }
+        _ => DatabaseInfo {
+            backend: "online".to_string(),
+            api_endpoint: order.first().map(|kind| kind.endpoint().to_string()),
+            providers: order
+                .iter()
+                .map(|kind| kind.endpoint().to_string())
+                .collect(),
+            file_path: None,
+            database_type: None,
+            build_date: None,
+            record_count: None,
+            node_count: None,
+            supports_ipv4: true,
+            supports_ipv6: true,
+        },
+    }
+}
+
+async fn lookup_online(addr: IpAddr) -> Result<LocationInfo, String> {
+    let result = lookup_with_providers(addr).await?;
+
+    Ok(LocationInfo {
+        ip: addr.to_string(),
+        location: result.location,
+        is_ipv4: addr.is_ipv4(),
+        source: result.provider,
+        cached: result.cached,
+        hostname: None,
+        resolved: Vec::new(),
     })
 }
 
 #[tauri::command]
 pub async fn debug_ip_query(ip: String) -> Result<String, String> {
-    let url = format!("{}?ip={}&type=json", IP_API_URL, ip);
+    let addr: IpAddr = ip
+        .trim()
+        .parse()
+        .map_err(|_| format!("无效的 IP 地址: {}", ip.trim()))?;
+    let url = format!("{}?ip={}&type=json", MIR6_API_URL, addr);
 
     match reqwest::get(&url).await {
         Ok(response) => match response.json::<Value>().await {
-            Ok(json) => match parse_json_result(&json) {
+            Ok(json) => match parse_mir6_result(&json) {
                 Ok(location) => Ok(format!(
                     "IP: {}\n国家: {}\n省份: {}\n城市: {}\n运营商: {}\n原始JSON: {}",
                     ip,
@@ -112,9 +378,44 @@ pub async fn debug_ip_query(ip: String) -> Result<String, String> {
 
 #[tauri::command]
 pub fn get_database_info() -> Result<DatabaseInfo, String> {
-    Ok(DatabaseInfo {
-        api_endpoint: IP_API_URL.to_string(),
-        supports_ipv4: true,
-        supports_ipv6: true,
-    })
+    let state = database_state()
+        .lock()
+        .map_err(|_| "数据库状态锁定失败".to_string())?;
+    Ok(describe_state(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lookup_targets() {
+        match parse_lookup_target("::ffff:8.8.8.8").unwrap() {
+            LookupTarget::Address(addr) => {
+                assert!(addr.is_ipv4());
+                assert_eq!(addr.to_string(), "8.8.8.8");
+            }
+            LookupTarget::Host(_) => panic!("should be address"),
+        }
+        assert!(matches!(
+            parse_lookup_target("[2001:4860::8888]").unwrap(),
+            LookupTarget::Address(IpAddr::V6(_))
+        ));
+        assert!(matches!(
+            parse_lookup_target("Example.COM.").unwrap(),
+            LookupTarget::Host(host) if host == "example.com"
+        ));
+        assert!(parse_lookup_target("1.2.3.4&type=xml").is_err());
+        assert!(parse_lookup_target("300.1.1.1").is_err());
+    }
+
+    #[test]
+    fn rejects_non_public_addresses() {
+        let err = ensure_public_address("10.1.2.3".parse().unwrap()).unwrap_err();
+        assert!(err.contains("10.0.0.0/8"));
+        assert!(ensure_public_address("fe80::1".parse().unwrap()).is_err());
+        assert!(ensure_public_address("224.0.0.5".parse().unwrap()).is_err());
+        assert!(ensure_public_address("8.8.8.8".parse().unwrap()).is_ok());
+        assert!(ensure_public_address("2002:c000:204::1".parse().unwrap()).is_ok());
+    }
 }
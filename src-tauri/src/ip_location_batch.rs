@@ -0,0 +1,388 @@
+use crate::ip_location::{ensure_public_address, normalize_ip, resolve_location, LocationBackend};
+use crate::ip_location_provider::flush_location_cache;
+use crate::subnet_batch::read_excel_column;
+use rust_xlsxwriter::{Format, FormatAlign, Workbook};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+const MAX_BATCH_IPS: usize = 5000;
+const DEFAULT_CONCURRENCY: usize = 8;
+const MAX_CONCURRENCY: usize = 32;
+const DEFAULT_RETRIES: u32 = 2;
+const MAX_RETRIES: u32 = 5;
+const RETRY_BASE_DELAY_MS: u64 = 500;
+const PROGRESS_EVENT: &str = "ip-location-batch-progress";
+const EXPORT_HEADERS: [&str; 9] = [
+    "序号",
+    "IP",
+    "出现次数",
+    "国家",
+    "省份",
+    "城市",
+    "运营商",
+    "查询次数",
+    "错误",
+];
+
+#[derive(Deserialize)]
+#[serde(tag = "source", rename_all = "camelCase")]
+pub enum IpLocationBatchInput {
+    Text {
+        lines: Vec<String>,
+    },
+    Excel {
+        file_path: String,
+        sheet_name: Option<String>,
+        column: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpLocationBatchRequest {
+    #[serde(flatten)]
+    pub input: IpLocationBatchInput,
+    pub backend: Option<LocationBackend>,
+    pub concurrency: Option<usize>,
+    pub retries: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IpLocationBatchRow {
+    pub index: usize,
+    pub ip: String,
+    pub is_ipv4: bool,
+    pub occurrences: usize,
+    pub country: String,
+    pub region: String,
+    pub city: String,
+    pub isp: String,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpLocationBatchResponse {
+    pub rows: Vec<IpLocationBatchRow>,
+    pub invalid_entries: Vec<String>,
+    pub total_inputs: usize,
+    pub unique_count: usize,
+    pub success_count: usize,
+    pub error_count: usize,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IpLocationBatchProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub ip: String,
+    pub success: bool,
+}
+
+#[tauri::command]
+pub async fn lookup_ip_locations_batch(
+    app: AppHandle,
+    request: IpLocationBatchRequest,
+) -> Result<IpLocationBatchResponse, String> {
+    let lines = match request.input {
+        IpLocationBatchInput::Text { lines } => lines,
+        IpLocationBatchInput::Excel {
+            file_path,
+            sheet_name,
+            column,
+        } => read_excel_column(&file_path, sheet_name, &column)?,
+    };
+
+    let (unique_ips, invalid_entries, total_inputs) = collect_unique_ips(&lines);
+    if unique_ips.is_empty() {
+        return Err("没有可查询的 IP 地址".to_string());
+    }
+    if unique_ips.len() > MAX_BATCH_IPS {
+        return Err(format!(
+            "一次最多查询 {} 个 IP，去重后为 {} 个",
+            MAX_BATCH_IPS,
+            unique_ips.len()
+        ));
+    }
+
+    let concurrency = request
+        .concurrency
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .clamp(1, MAX_CONCURRENCY);
+    let retries = request.retries.unwrap_or(DEFAULT_RETRIES).min(MAX_RETRIES);
+    let backend = request.backend.unwrap_or(LocationBackend::Online);
+
+    let total = unique_ips.len();
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let mut tasks = JoinSet::new();
+
+    for (idx, (ip, occurrences)) in unique_ips.into_iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let completed = completed.clone();
+        let backend = backend.clone();
+        let app = app.clone();
+
+        tasks.spawn(async move {
+            let index = idx + 1;
+            let row = match semaphore.acquire_owned().await {
+                Ok(_permit) => lookup_with_retry(index, ip, occurrences, backend, retries).await,
+                Err(_) => failed_row(index, ip, occurrences, 0, "并发控制已关闭".into()),
+            };
+
+            let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            let _ = app.emit(
+                PROGRESS_EVENT,
+                IpLocationBatchProgress {
+                    completed: done,
+                    total,
+                    ip: row.ip.clone(),
+                    success: row.error.is_none(),
+                },
+            );
+            row
+        });
+    }
+
+    let mut rows = Vec::with_capacity(total);
+    while let Some(joined) = tasks.join_next().await {
+        rows.push(joined.map_err(|err| format!("查询任务异常: {err}"))?);
+    }
+    rows.sort_by_key(|row| row.index);
+    flush_location_cache()?;
+
+    let error_count = rows.iter().filter(|row| row.error.is_some()).count();
+    Ok(IpLocationBatchResponse {
+        success_count: rows.len() - error_count,
+        error_count,
+        unique_count: rows.len(),
+        total_inputs,
+        invalid_entries,
+        rows,
+    })
+}
+
+#[tauri::command]
+pub fn export_ip_location_batch(path: String, rows: Vec<IpLocationBatchRow>) -> Result<(), String> {
+    if rows.is_empty() {
+        return Err("没有可导出的查询结果".into());
+    }
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let header_format = Format::new().set_bold().set_align(FormatAlign::Center);
+
+    for (col, header) in EXPORT_HEADERS.iter().enumerate() {
+        worksheet
+            .set_column_width(col as u16, 18.0)
+            .map_err(|err| format!("设置列宽失败: {err}"))?;
+        worksheet
+            .write_string_with_format(0, col as u16, *header, &header_format)
+            .map_err(|err| format!("写入表头失败: {err}"))?;
+    }
+
+    for (row_idx, row) in rows.iter().enumerate() {
+        let values = [
+            row.index.to_string(),
+            row.ip.clone(),
+            row.occurrences.to_string(),
+            row.country.clone(),
+            row.region.clone(),
+            row.city.clone(),
+            row.isp.clone(),
+            row.attempts.to_string(),
+            row.error.clone().unwrap_or_default(),
+        ];
+        for (col_idx, value) in values.iter().enumerate() {
+            worksheet
+                .write_string((row_idx + 1) as u32, col_idx as u16, value)
+                .map_err(|err| format!("写入查询结果失败: {err}"))?;
+        }
+    }
+
+    workbook
+        .save(path)
+        .map_err(|err| format!("保存文件失败: {err}"))?;
+    Ok(())
+}
+
+// 在线接口偶发超时或限流，失败后按次数递增等待再重试；离线库结果确定，不重试。
+// 内网、保留等非公网地址必然失败，不发起查询，attempts 记为 0
+async fn lookup_with_retry(
+    index: usize,
+    ip: IpAddr,
+    occurrences: usize,
+    backend: LocationBackend,
+    retries: u32,
+) -> IpLocationBatchRow {
+    if let Err(err) = ensure_public_address(ip) {
+        return failed_row(index, ip, occurrences, 0, err);
+    }
+
+    let max_attempts = match backend {
+        LocationBackend::Online => retries + 1,
+        _ => 1,
+    };
+
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match resolve_location(ip.to_string(), Some(backend.clone())).await {
+            Ok(info) => {
+                return IpLocationBatchRow {
+                    index,
+                    ip: ip.to_string(),
+                    is_ipv4: ip.is_ipv4(),
+                    occurrences,
+                    country: info.location.country,
+                    region: info.location.region,
+                    city: info.location.city,
+                    isp: info.location.isp,
+                    attempts,
+                    error: None,
+                }
+            }
+            Err(err) if attempts >= max_attempts => {
+                return failed_row(index, ip, occurrences, attempts, err)
+            }
+            Err(_) => {
+                let delay = RETRY_BASE_DELAY_MS * u64::from(attempts);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+        }
+    }
+}
+
+fn failed_row(
+    index: usize,
+    ip: IpAddr,
+    occurrences: usize,
+    attempts: u32,
+    error: String,
+) -> IpLocationBatchRow {
+    IpLocationBatchRow {
+        index,
+        ip: ip.to_string(),
+        is_ipv4: ip.is_ipv4(),
+        occurrences,
+        country: String::new(),
+        region: String::new(),
+        city: String::new(),
+        isp: String::new(),
+        attempts,
+        error: Some(error),
+    }
+}
+
+// 从每行中提取所有 IP（兼容防火墙日志等整行粘贴），按首次出现顺序去重并统计次数
+fn collect_unique_ips(lines: &[String]) -> (Vec<(IpAddr, usize)>, Vec<String>, usize) {
+    let mut unique: Vec<(IpAddr, usize)> = Vec::new();
+    let mut positions: HashMap<IpAddr, usize> = HashMap::new();
+    let mut invalid_entries = Vec::new();
+    let mut total_inputs = 0;
+
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let found: Vec<IpAddr> = trimmed
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '"' | '\'' | '|'))
+            .filter_map(parse_token)
+            .map(normalize_ip)
+            .collect();
+        if found.is_empty() {
+            invalid_entries.push(trimmed.to_string());
+            continue;
+        }
+
+        for ip in found {
+            total_inputs += 1;
+            match positions.get(&ip) {
+                Some(&position) => unique[position].1 += 1,
+                None => {
+                    positions.insert(ip, unique.len());
+                    unique.push((ip, 1));
+                }
+            }
+        }
+    }
+
+    (unique, invalid_entries, total_inputs)
+}
+
+// 允许 1.2.3.4:443、[2001:db8::1]:443 以及 src=1.2.3.4 这类日志写法
+fn parse_token(token: &str) -> Option<IpAddr> {
+    let token = token
+        .rsplit_once('=')
+        .map_or(token, |(_, value)| value)
+        .trim_matches(|c: char| matches!(c, '(' | ')' | '<' | '>'));
+    if token.is_empty() {
+        return None;
+    }
+    if let Ok(ip) = token.parse::<IpAddr>() {
+        return Some(ip);
+    }
+
+    if let Some(rest) = token.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    let (host, port) = token.rsplit_once(':')?;
+    if port.chars().all(|c| c.is_ascii_digit()) && host.contains('.') {
+        return host.parse().ok();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_ips_from_log_lines() {
+        let lines = vec![
+            "src=203.0.113.5 dst=10.0.0.1:443 action=deny".to_string(),
+            "203.0.113.5".to_string(),
+            "[2001:db8::1]:8443, 198.51.100.7".to_string(),
+            "no address here".to_string(),
+            "".to_string(),
+        ];
+        let (unique, invalid, total) = collect_unique_ips(&lines);
+
+        let ips: Vec<String> = unique.iter().map(|(ip, _)| ip.to_string()).collect();
+        assert_eq!(
+            ips,
+            vec!["203.0.113.5", "10.0.0.1", "2001:db8::1", "198.51.100.7"]
+        );
+        assert_eq!(unique[0].1, 2);
+        assert_eq!(total, 5);
+        assert_eq!(invalid, vec!["no address here"]);
+    }
+
+    #[tokio::test]
+    async fn skips_retries_for_private_addresses() {
+        let started = std::time::Instant::now();
+        let row = lookup_with_retry(
+            1,
+            "10.0.0.1".parse().unwrap(),
+            1,
+            LocationBackend::Online,
+            3,
+        )
+        .await;
+        assert_eq!(row.attempts, 0);
+        assert!(row.error.unwrap().contains("不是公网地址"));
+        assert!(started.elapsed() < Duration::from_millis(RETRY_BASE_DELAY_MS));
+    }
+}
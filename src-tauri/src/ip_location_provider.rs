@@ -0,0 +1,542 @@
+use crate::ip_location::IpLocationResult;
+use crate::isp_manager::{
+    config_dir, find_isp_for_ip, load_isp_data_internal, read_text_file, write_atomic,
+    ISP_DATA_FILENAME,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+pub(crate) const MIR6_API_URL: &str = "https://api.mir6.com/api/ip";
+const IP_API_URL: &str = "http://ip-api.com/json";
+const IPWHOIS_API_URL: &str = "https://ipwho.is";
+const IPINFO_API_URL: &str = "https://ipinfo.io";
+
+const PROVIDER_CONFIG_FILENAME: &str = "location_providers.yaml";
+const CACHE_FILENAME: &str = "location_cache.json";
+const DEFAULT_CACHE_TTL_HOURS: u64 = 168;
+const REQUEST_TIMEOUT_SECS: u64 = 8;
+
+type ProviderFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<IpLocationResult, String>> + Send + 'a>>;
+
+pub(crate) trait LocationProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn lookup(&self, ip: IpAddr) -> ProviderFuture<'_>;
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderKind {
+    LocalIsp,
+    Mir6,
+    IpApi,
+    IpWhois,
+    IpInfo,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationProviderConfig {
+    pub order: Vec<ProviderKind>,
+    pub cache_ttl_hours: u64,
+}
+
+impl Default for LocationProviderConfig {
+    fn default() -> Self {
+        LocationProviderConfig {
+            order: vec![
+                ProviderKind::Mir6,
+                ProviderKind::IpApi,
+                ProviderKind::IpWhois,
+            ],
+            cache_ttl_hours: DEFAULT_CACHE_TTL_HOURS,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationCacheSummary {
+    pub removed_count: usize,
+    pub cache_file: String,
+}
+
+pub(crate) struct ProviderLookup {
+    pub location: IpLocationResult,
+    pub provider: String,
+    pub cached: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CachedLocation {
+    provider: String,
+    location: IpLocationResult,
+    cached_at: i64,
+}
+
+#[derive(Default)]
+struct ProviderState {
+    config: Option<LocationProviderConfig>,
+    cache: Option<HashMap<String, CachedLocation>>,
+    dirty: bool,
+}
+
+// 通用 JSON 接口：地址模板中的 {ip} 会被替换，响应交给各自的解析函数
+struct JsonApiProvider {
+    name: &'static str,
+    url_template: &'static str,
+    parse: fn(&Value) -> Result<IpLocationResult, String>,
+}
+
+impl LocationProvider for JsonApiProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn lookup(&self, ip: IpAddr) -> ProviderFuture<'_> {
+        Box::pin(async move {
+            let url = self.url_template.replace("{ip}", &ip.to_string());
+            let response = http_client()
+                .get(&url)
+                .send()
+                .await
+                .map_err(|err| format!("请求失败: {}", err))?;
+            let json = response
+                .json::<Value>()
+                .await
+                .map_err(|err| format!("获取 JSON 响应失败: {}", err))?;
+            (self.parse)(&json)
+        })
+    }
+}
+
+// 本地运营商网段数据（isp_data.yaml），只能给出国内三大运营商归属
+struct LocalIspProvider;
+
+impl LocationProvider for LocalIspProvider {
+    fn name(&self) -> &'static str {
+        "localIsp"
+    }
+
+    fn lookup(&self, ip: IpAddr) -> ProviderFuture<'_> {
+        Box::pin(async move {
+            let IpAddr::V4(ipv4) = ip else {
+                return Err("本地运营商数据仅支持 IPv4".to_string());
+            };
+            let data = load_isp_data_internal()?;
+            let (code, range) =
+                find_isp_for_ip(&ipv4, &data).ok_or_else(|| "本地运营商数据未命中".to_string())?;
+            let isp = match code.as_str() {
+                "DX" => "中国电信".to_string(),
+                "LT" => "中国联通".to_string(),
+                "YD" => "中国移动".to_string(),
+                _ => code.clone(),
+            };
+
+            Ok(IpLocationResult {
+                country: "中国".to_string(),
+                region: "未知".to_string(),
+                city: "未知".to_string(),
+                isp,
+                raw_info: format!("{} {}", code, range),
+            })
+        })
+    }
+}
+
+impl ProviderKind {
+    fn provider(self) -> Box<dyn LocationProvider> {
+        match self {
+            ProviderKind::LocalIsp => Box::new(LocalIspProvider),
+            ProviderKind::Mir6 => Box::new(JsonApiProvider {
+                name: "mir6",
+                url_template: "https://api.mir6.com/api/ip?ip={ip}&type=json",
+                parse: parse_mir6_result,
+            }),
+            ProviderKind::IpApi => Box::new(JsonApiProvider {
+                name: "ipApi",
+                url_template: "http://ip-api.com/json/{ip}?lang=zh-CN",
+                parse: parse_ip_api_result,
+            }),
+            ProviderKind::IpWhois => Box::new(JsonApiProvider {
+                name: "ipWhois",
+                url_template: "https://ipwho.is/{ip}?lang=zh-CN",
+                parse: parse_ipwhois_result,
+            }),
+            ProviderKind::IpInfo => Box::new(JsonApiProvider {
+                name: "ipInfo",
+                url_template: "https://ipinfo.io/{ip}/json",
+                parse: parse_ipinfo_result,
+            }),
+        }
+    }
+
+    pub(crate) fn endpoint(self) -> &'static str {
+        match self {
+            ProviderKind::LocalIsp => ISP_DATA_FILENAME,
+            ProviderKind::Mir6 => MIR6_API_URL,
+            ProviderKind::IpApi => IP_API_URL,
+            ProviderKind::IpWhois => IPWHOIS_API_URL,
+            ProviderKind::IpInfo => IPINFO_API_URL,
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_location_provider_config() -> Result<LocationProviderConfig, String> {
+    current_config()
+}
+
+#[tauri::command]
+pub fn update_location_provider_config(
+    config: LocationProviderConfig,
+) -> Result<LocationProviderConfig, String> {
+    if config.order.is_empty() {
+        return Err("至少需要启用一个查询来源".to_string());
+    }
+    let mut order: Vec<ProviderKind> = Vec::new();
+    for kind in config.order {
+        if !order.contains(&kind) {
+            order.push(kind);
+        }
+    }
+    let config = LocationProviderConfig {
+        order,
+        cache_ttl_hours: config.cache_ttl_hours,
+    };
+
+    let serialized =
+        serde_yaml::to_string(&config).map_err(|err| format!("序列化查询来源配置失败: {err}"))?;
+    write_atomic(&config_dir()?.join(PROVIDER_CONFIG_FILENAME), &serialized)?;
+
+    provider_state()
+        .lock()
+        .map_err(|_| "查询来源状态锁定失败".to_string())?
+        .config = Some(config.clone());
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn clear_location_cache() -> Result<LocationCacheSummary, String> {
+    let mut state = provider_state()
+        .lock()
+        .map_err(|_| "查询来源状态锁定失败".to_string())?;
+    let removed_count = load_cache(&mut state)?.len();
+    state.cache = Some(HashMap::new());
+    state.dirty = false;
+
+    let cache_file = config_dir()?.join(CACHE_FILENAME);
+    write_atomic(&cache_file, "{}")?;
+    Ok(LocationCacheSummary {
+        removed_count,
+        cache_file: cache_file.display().to_string(),
+    })
+}
+
+pub(crate) fn current_config() -> Result<LocationProviderConfig, String> {
+    let mut state = provider_state()
+        .lock()
+        .map_err(|_| "查询来源状态锁定失败".to_string())?;
+    if let Some(config) = &state.config {
+        return Ok(config.clone());
+    }
+
+    let path = config_dir()?.join(PROVIDER_CONFIG_FILENAME);
+    let config = match read_text_file(&path)? {
+        Some(content) if !content.trim().is_empty() => {
+            serde_yaml::from_str(&content).map_err(|err| format!("解析查询来源配置失败: {err}"))?
+        }
+        _ => LocationProviderConfig::default(),
+    };
+    state.config = Some(config.clone());
+    Ok(config)
+}
+
+// 先查本地缓存，未命中时按配置顺序逐个尝试，全部失败时汇总各来源的错误
+pub(crate) async fn lookup_with_providers(ip: IpAddr) -> Result<ProviderLookup, String> {
+    let config = current_config()?;
+    let ttl_secs = config.cache_ttl_hours.saturating_mul(3600);
+    let key = ip.to_string();
+
+    if ttl_secs > 0 {
+        let mut state = provider_state()
+            .lock()
+            .map_err(|_| "查询来源状态锁定失败".to_string())?;
+        let cache = load_cache(&mut state)?;
+        if let Some(entry) = cache.get(&key) {
+            if is_fresh(entry.cached_at, Utc::now().timestamp(), ttl_secs) {
+                return Ok(ProviderLookup {
+                    location: entry.location.clone(),
+                    provider: entry.provider.clone(),
+                    cached: true,
+                });
+            }
+        }
+    }
+
+    let mut errors = Vec::new();
+    for kind in config.order {
+        let provider = kind.provider();
+        match provider.lookup(ip).await {
+            Ok(location) => {
+                if ttl_secs > 0 {
+                    let mut state = provider_state()
+                        .lock()
+                        .map_err(|_| "查询来源状态锁定失败".to_string())?;
+                    load_cache(&mut state)?.insert(
+                        key,
+                        CachedLocation {
+                            provider: provider.name().to_string(),
+                            location: location.clone(),
+                            cached_at: Utc::now().timestamp(),
+                        },
+                    );
+                    state.dirty = true;
+                }
+                return Ok(ProviderLookup {
+                    location,
+                    provider: provider.name().to_string(),
+                    cached: false,
+                });
+            }
+            Err(err) => errors.push(format!("{}: {}", provider.name(), err)),
+        }
+    }
+
+    Err(format!("所有查询来源均失败: {}", errors.join("; ")))
+}
+
+// 批量查询时只在结束后写一次磁盘，写入前顺带清理过期条目
+pub(crate) fn flush_location_cache() -> Result<(), String> {
+    let ttl_secs = current_config()?.cache_ttl_hours.saturating_mul(3600);
+    let mut state = provider_state()
+        .lock()
+        .map_err(|_| "查询来源状态锁定失败".to_string())?;
+    if !state.dirty {
+        return Ok(());
+    }
+
+    let now = Utc::now().timestamp();
+    let cache = load_cache(&mut state)?;
+    cache.retain(|_, entry| is_fresh(entry.cached_at, now, ttl_secs));
+    let serialized =
+        serde_json::to_string(cache).map_err(|err| format!("序列化查询缓存失败: {err}"))?;
+    write_atomic(&config_dir()?.join(CACHE_FILENAME), &serialized)?;
+    state.dirty = false;
+    Ok(())
+}
+
+fn provider_state() -> &'static Mutex<ProviderState> {
+    static STATE: OnceLock<Mutex<ProviderState>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(ProviderState::default()))
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+fn load_cache(state: &mut ProviderState) -> Result<&mut HashMap<String, CachedLocation>, String> {
+    if state.cache.is_none() {
+        let path = config_dir()?.join(CACHE_FILENAME);
+        // 缓存损坏时直接丢弃，不影响查询
+        let cache = read_text_file(&path)?
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        state.cache = Some(cache);
+    }
+    Ok(state.cache.get_or_insert_with(HashMap::new))
+}
+
+fn is_fresh(cached_at: i64, now: i64, ttl_secs: u64) -> bool {
+    now.saturating_sub(cached_at) < ttl_secs.min(i64::MAX as u64) as i64
+}
+
+fn text_or_unknown(value: &Value) -> String {
+    match value.as_str().map(str::trim) {
+        Some(text) if !text.is_empty() => text.to_string(),
+        _ => "未知".to_string(),
+    }
+}
+
+pub(crate) fn parse_mir6_result(json: &Value) -> Result<IpLocationResult, String> {
+    // 成功时为 {"code": 200, "data": {...}}；限流或出错时没有 data
+    let code_ok = json["code"].as_i64().is_none_or(|code| code == 200);
+    if !code_ok || !json["data"].is_object() {
+        return Err(format!(
+            "mir6 返回失败: {}",
+            json["msg"].as_str().unwrap_or("未知错误")
+        ));
+    }
+
+    // 提取数据字段
+    let country = json["data"]["country"]
+        .as_str()
+        .unwrap_or("未知")
+        .to_string();
+
+    let region = json["data"]["province"]
+        .as_str()
+        .unwrap_or("未知")
+        .to_string();
+
+    let city = json["data"]["city"].as_str().unwrap_or("未知").to_string();
+
+    let isp = json["data"]["isp"].as_str().unwrap_or("未知").to_string();
+
+    // 如果城市未知且省份是直辖市，设为相同
+    let final_city = if city == "未知"
+        && (region.ends_with("市") || region.ends_with("县") || region.ends_with("区"))
+    {
+        region.clone()
+    } else {
+        city
+    };
+
+    Ok(IpLocationResult {
+        country,
+        region,
+        city: final_city,
+        isp,
+        raw_info: json.to_string(),
+    })
+}
+
+fn parse_ip_api_result(json: &Value) -> Result<IpLocationResult, String> {
+    if json["status"].as_str() != Some("success") {
+        return Err(format!(
+            "ip-api 返回失败: {}",
+            json["message"].as_str().unwrap_or("未知错误")
+        ));
+    }
+
+    let isp = if json["isp"].as_str().is_some_and(|isp| !isp.is_empty()) {
+        text_or_unknown(&json["isp"])
+    } else {
+        text_or_unknown(&json["org"])
+    };
+
+    Ok(IpLocationResult {
+        country: text_or_unknown(&json["country"]),
+        region: text_or_unknown(&json["regionName"]),
+        city: text_or_unknown(&json["city"]),
+        isp,
+        raw_info: json.to_string(),
+    })
+}
+
+fn parse_ipwhois_result(json: &Value) -> Result<IpLocationResult, String> {
+    if json["success"].as_bool() != Some(true) {
+        return Err(format!(
+            "ipwho.is 返回失败: {}",
+            json["message"].as_str().unwrap_or("未知错误")
+        ));
+    }
+
+    let connection = &json["connection"];
+    let isp = if connection["isp"]
+        .as_str()
+        .is_some_and(|isp| !isp.is_empty())
+    {
+        text_or_unknown(&connection["isp"])
+    } else {
+        text_or_unknown(&connection["org"])
+    };
+
+    Ok(IpLocationResult {
+        country: text_or_unknown(&json["country"]),
+        region: text_or_unknown(&json["region"]),
+        city: text_or_unknown(&json["city"]),
+        isp,
+        raw_info: json.to_string(),
+    })
+}
+
+fn parse_ipinfo_result(json: &Value) -> Result<IpLocationResult, String> {
+    if let Some(message) = json["error"]["message"].as_str() {
+        return Err(format!("ipinfo 返回失败: {}", message));
+    }
+    if json["bogon"].as_bool() == Some(true) {
+        return Err("ipinfo 认为该地址不可公网路由".to_string());
+    }
+
+    // org 形如 "AS4134 CHINANET-BACKBONE"，去掉前面的 AS 号
+    let isp = json["org"]
+        .as_str()
+        .map(|org| match org.split_once(' ') {
+            Some((asn, name)) if asn.starts_with("AS") => name.to_string(),
+            _ => org.to_string(),
+        })
+        .filter(|isp| !isp.is_empty())
+        .unwrap_or_else(|| "未知".to_string());
+
+    Ok(IpLocationResult {
+        country: text_or_unknown(&json["country"]),
+        region: text_or_unknown(&json["region"]),
+        city: text_or_unknown(&json["city"]),
+        isp,
+        raw_info: json.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_provider_responses() {
+        let ip_api = serde_json::json!({
+            "status": "success", "country": "中国", "regionName": "广东",
+            "city": "深圳", "isp": "Chinanet"
+        });
+        let location = parse_ip_api_result(&ip_api).expect("should parse");
+        assert_eq!(location.region, "广东");
+        assert_eq!(location.isp, "Chinanet");
+
+        let failed = serde_json::json!({ "status": "fail", "message": "private range" });
+        assert!(parse_ip_api_result(&failed).is_err());
+
+        let mir6 = serde_json::json!({
+            "code": 200, "msg": "success",
+            "data": { "country": "中国", "province": "北京市", "isp": "联通" }
+        });
+        let location = parse_mir6_result(&mir6).expect("should parse");
+        assert_eq!(location.city, "北京市");
+
+        let limited = serde_json::json!({ "code": 429, "msg": "请求过于频繁" });
+        let Err(err) = parse_mir6_result(&limited) else {
+            panic!("限流响应不应视为成功");
+        };
+        assert!(err.contains("请求过于频繁"));
+        let error_with_data = serde_json::json!({ "code": 500, "msg": "error", "data": {} });
+        assert!(parse_mir6_result(&error_with_data).is_err());
+
+        let ipinfo = serde_json::json!({
+            "ip": "1.1.1.1", "city": "Brisbane", "region": "Queensland",
+            "country": "AU", "org": "AS13335 Cloudflare, Inc."
+        });
+        let location = parse_ipinfo_result(&ipinfo).expect("should parse");
+        assert_eq!(location.isp, "Cloudflare, Inc.");
+        assert_eq!(location.country, "AU");
+    }
+
+    #[test]
+    fn cache_entries_expire_after_ttl() {
+        assert!(is_fresh(1_000, 1_000 + 3599, 3600));
+        assert!(!is_fresh(1_000, 1_000 + 3600, 3600));
+        assert!(!is_fresh(1_000, 1_000, 0));
+    }
+}
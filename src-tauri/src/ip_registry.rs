@@ -0,0 +1,242 @@
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecialPurposeInfo {
+    pub block: String,
+    pub name: String,
+    pub rfc: String,
+    pub source: Option<bool>,
+    pub destination: Option<bool>,
+    pub forwardable: Option<bool>,
+    pub globally_reachable: Option<bool>,
+    pub reserved_by_protocol: Option<bool>,
+}
+
+// 标志位依次为 source、destination、forwardable、globally reachable、reserved-by-protocol，
+// None 表示 IANA 注册表中为 N/A 或该地址块不在特殊用途注册表中（如组播）
+type Flags = [Option<bool>; 5];
+
+struct RegistryEntry<T> {
+    network: T,
+    prefix: u32,
+    name: &'static str,
+    rfc: &'static str,
+    flags: Flags,
+}
+
+const T: Option<bool> = Some(true);
+const F: Option<bool> = Some(false);
+const NA: Option<bool> = None;
+
+const fn v4(
+    octets: [u8; 4],
+    prefix: u32,
+    name: &'static str,
+    rfc: &'static str,
+    flags: Flags,
+) -> RegistryEntry<u32> {
+    RegistryEntry {
+        network: u32::from_be_bytes(octets),
+        prefix,
+        name,
+        rfc,
+        flags,
+    }
+}
+
+const fn v6(
+    network: u128,
+    prefix: u32,
+    name: &'static str,
+    rfc: &'static str,
+    flags: Flags,
+) -> RegistryEntry<u128> {
+    RegistryEntry {
+        network,
+        prefix,
+        name,
+        rfc,
+        flags,
+    }
+}
+
+// IANA IPv4 Special-Purpose Address Registry，另补充组播及组播文档地址块
+#[rustfmt::skip]
+const IPV4_REGISTRY: &[RegistryEntry<u32>] = &[
+    v4([0, 0, 0, 0], 8, "This network", "RFC 791", [T, F, F, F, T]),
+    v4([0, 0, 0, 0], 32, "This host on this network", "RFC 1122", [T, F, F, F, T]),
+    v4([10, 0, 0, 0], 8, "Private-Use", "RFC 1918", [T, T, T, F, F]),
+    v4([100, 64, 0, 0], 10, "Shared Address Space", "RFC 6598", [T, T, T, F, F]),
+    v4([127, 0, 0, 0], 8, "Loopback", "RFC 1122", [F, F, F, F, T]),
+    v4([169, 254, 0, 0], 16, "Link Local", "RFC 3927", [T, T, F, F, T]),
+    v4([172, 16, 0, 0], 12, "Private-Use", "RFC 1918", [T, T, T, F, F]),
+    v4([192, 0, 0, 0], 24, "IETF Protocol Assignments", "RFC 6890", [F, F, F, F, F]),
+    v4([192, 0, 0, 0], 29, "IPv4 Service Continuity Prefix", "RFC 7335", [T, T, T, F, F]),
+    v4([192, 0, 0, 8], 32, "IPv4 dummy address", "RFC 7600", [T, F, F, F, F]),
+    v4([192, 0, 0, 9], 32, "Port Control Protocol Anycast", "RFC 7723", [T, T, T, T, F]),
+    v4([192, 0, 0, 10], 32, "Traversal Using Relays around NAT Anycast", "RFC 8155", [T, T, T, T, F]),
+    v4([192, 0, 0, 170], 32, "NAT64/DNS64 Discovery", "RFC 8880", [F, F, F, F, T]),
+    v4([192, 0, 0, 171], 32, "NAT64/DNS64 Discovery", "RFC 8880", [F, F, F, F, T]),
+    v4([192, 0, 2, 0], 24, "Documentation (TEST-NET-1)", "RFC 5737", [F, F, F, F, F]),
+    v4([192, 31, 196, 0], 24, "AS112-v4", "RFC 7535", [T, T, T, T, F]),
+    v4([192, 52, 193, 0], 24, "AMT", "RFC 7450", [T, T, T, T, F]),
+    v4([192, 88, 99, 0], 24, "Deprecated (6to4 Relay Anycast)", "RFC 7526", [NA, NA, NA, NA, NA]),
+    v4([192, 88, 99, 2], 32, "6a44-relay anycast address", "RFC 6751", [T, T, T, F, F]),
+    v4([192, 168, 0, 0], 16, "Private-Use", "RFC 1918", [T, T, T, F, F]),
+    v4([192, 175, 48, 0], 24, "Direct Delegation AS112 Service", "RFC 7534", [T, T, T, T, F]),
+    v4([198, 18, 0, 0], 15, "Benchmarking", "RFC 2544", [T, T, T, F, F]),
+    v4([198, 51, 100, 0], 24, "Documentation (TEST-NET-2)", "RFC 5737", [F, F, F, F, F]),
+    v4([203, 0, 113, 0], 24, "Documentation (TEST-NET-3)", "RFC 5737", [F, F, F, F, F]),
+    v4([224, 0, 0, 0], 4, "Multicast", "RFC 5771", [NA, NA, NA, NA, NA]),
+    v4([233, 252, 0, 0], 24, "Multicast Documentation (MCAST-TEST-NET)", "RFC 5771", [NA, NA, NA, NA, NA]),
+    v4([240, 0, 0, 0], 4, "Reserved", "RFC 1112", [F, F, F, F, T]),
+    v4([255, 255, 255, 255], 32, "Limited Broadcast", "RFC 8190", [F, T, F, F, T]),
+];
+
+// IANA IPv6 Special-Purpose Address Registry，另补充组播地址块
+#[rustfmt::skip]
+const IPV6_REGISTRY: &[RegistryEntry<u128>] = &[
+    v6(1, 128, "Loopback Address", "RFC 4291", [F, F, F, F, T]),
+    v6(0, 128, "Unspecified Address", "RFC 4291", [T, F, F, F, T]),
+    v6(0xffff << 32, 96, "IPv4-mapped Address", "RFC 4291", [F, F, F, F, T]),
+    v6(0x0064_ff9b << 96, 96, "IPv4-IPv6 Translat.", "RFC 6052", [T, T, T, T, F]),
+    v6(0x0064_ff9b_0001 << 80, 48, "IPv4-IPv6 Translat.", "RFC 8215", [T, T, T, F, F]),
+    v6(0x0100 << 112, 64, "Discard-Only Address Block", "RFC 6666", [T, T, T, F, F]),
+    v6((0x0100 << 112) | (1 << 64), 64, "Dummy IPv6 Prefix", "RFC 9780", [T, F, F, F, F]),
+    v6(0x2001 << 112, 23, "IETF Protocol Assignments", "RFC 2928", [F, F, F, F, F]),
+    v6(0x2001 << 112, 32, "TEREDO", "RFC 4380", [T, T, T, NA, F]),
+    v6((0x2001_0001 << 96) | 1, 128, "Port Control Protocol Anycast", "RFC 7723", [T, T, T, T, F]),
+    v6((0x2001_0001 << 96) | 2, 128, "Traversal Using Relays around NAT Anycast", "RFC 8155", [T, T, T, T, F]),
+    v6((0x2001_0001 << 96) | 3, 128, "DNS-SD Service Registration Protocol Anycast", "RFC 9665", [T, T, T, T, F]),
+    v6(0x2001_0002 << 96, 48, "Benchmarking", "RFC 5180", [T, T, T, F, F]),
+    v6(0x2001_0003 << 96, 32, "AMT", "RFC 7450", [T, T, T, T, F]),
+    v6(0x2001_0004_0112 << 80, 48, "AS112-v6", "RFC 7535", [T, T, T, T, F]),
+    v6(0x2001_0010 << 96, 28, "Deprecated (previously ORCHID)", "RFC 4843", [NA, NA, NA, NA, NA]),
+    v6(0x2001_0020 << 96, 28, "ORCHIDv2", "RFC 7343", [T, T, T, T, F]),
+    v6(0x2001_0030 << 96, 28, "Drone Remote ID Protocol Entity Tags (DETs) Prefix", "RFC 9374", [T, T, T, T, F]),
+    v6(0x2001_0db8 << 96, 32, "Documentation", "RFC 3849", [F, F, F, F, F]),
+    v6(0x2002 << 112, 16, "6to4", "RFC 3056", [T, T, T, NA, F]),
+    v6(0x2620_004f_8000 << 80, 48, "Direct Delegation AS112 Service", "RFC 7534", [T, T, T, T, F]),
+    v6(0x3fff << 112, 20, "Documentation", "RFC 9637", [F, F, F, F, F]),
+    v6(0x5f00 << 112, 16, "Segment Routing (SRv6) SIDs", "RFC 9602", [T, T, T, F, F]),
+    v6(0xfc00 << 112, 7, "Unique-Local", "RFC 4193", [T, T, T, F, F]),
+    v6(0xfe80 << 112, 10, "Link-Local Unicast", "RFC 4291", [T, T, F, F, T]),
+    v6(0xff00 << 112, 8, "Multicast", "RFC 4291", [NA, NA, NA, NA, NA]),
+];
+
+#[tauri::command]
+pub fn lookup_special_purpose(ip: String) -> Result<Option<SpecialPurposeInfo>, String> {
+    let addr: IpAddr = ip
+        .trim()
+        .parse()
+        .map_err(|_| format!("无效的 IP 地址: {}", ip.trim()))?;
+
+    Ok(match addr {
+        IpAddr::V4(v4) => lookup_ipv4(u32::from(v4)),
+        IpAddr::V6(v6) => lookup_ipv6(u128::from(v6)),
+    })
+}
+
+pub(crate) fn lookup_ipv4(ip: u32) -> Option<SpecialPurposeInfo> {
+    IPV4_REGISTRY
+        .iter()
+        .filter(|entry| ip & mask_v4(entry.prefix) == entry.network)
+        .max_by_key(|entry| entry.prefix)
+        .map(|entry| {
+            to_info(
+                entry,
+                format!("{}/{}", Ipv4Addr::from(entry.network), entry.prefix),
+            )
+        })
+}
+
+pub(crate) fn lookup_ipv6(ip: u128) -> Option<SpecialPurposeInfo> {
+    IPV6_REGISTRY
+        .iter()
+        .filter(|entry| ip & mask_v6(entry.prefix) == entry.network)
+        .max_by_key(|entry| entry.prefix)
+        .map(|entry| {
+            to_info(
+                entry,
+                format!("{}/{}", Ipv6Addr::from(entry.network), entry.prefix),
+            )
+        })
+}
+
+fn to_info<N>(entry: &RegistryEntry<N>, block: String) -> SpecialPurposeInfo {
+    let [source, destination, forwardable, globally_reachable, reserved_by_protocol] = entry.flags;
+    SpecialPurposeInfo {
+        block,
+        name: entry.name.to_string(),
+        rfc: entry.rfc.to_string(),
+        source,
+        destination,
+        forwardable,
+        globally_reachable,
+        reserved_by_protocol,
+    }
+}
+
+fn mask_v4(prefix: u32) -> u32 {
+    if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - prefix)
+    }
+}
+
+fn mask_v6(prefix: u32) -> u128 {
+    if prefix == 0 {
+        0
+    } else {
+        u128::MAX << (128 - prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v4_name(ip: &str) -> Option<String> {
+        lookup_ipv4(u32::from(ip.parse::<Ipv4Addr>().unwrap())).map(|info| info.name)
+    }
+
+    #[test]
+    fn ipv4_lookup_prefers_most_specific_block() {
+        assert_eq!(
+            v4_name("192.0.0.9").as_deref(),
+            Some("Port Control Protocol Anycast")
+        );
+        assert_eq!(
+            v4_name("192.0.0.100").as_deref(),
+            Some("IETF Protocol Assignments")
+        );
+        assert_eq!(v4_name("198.19.1.1").as_deref(), Some("Benchmarking"));
+        assert_eq!(
+            v4_name("233.252.0.1").as_deref(),
+            Some("Multicast Documentation (MCAST-TEST-NET)")
+        );
+        assert_eq!(
+            v4_name("255.255.255.255").as_deref(),
+            Some("Limited Broadcast")
+        );
+        assert_eq!(v4_name("8.8.8.8"), None);
+    }
+
+    #[test]
+    fn ipv6_lookup_reports_flags() {
+        let info = lookup_special_purpose("2001:db8::1".into())
+            .unwrap()
+            .expect("documentation block");
+        assert_eq!(info.block, "2001:db8::/32");
+        assert_eq!(info.globally_reachable, Some(false));
+
+        let teredo = lookup_special_purpose("2001:0:4136:e378::1".into())
+            .unwrap()
+            .expect("teredo block");
+        assert_eq!(teredo.name, "TEREDO");
+        assert_eq!(teredo.globally_reachable, None);
+    }
+}
@@ -1,3 +1,7 @@
+use crate::ip_registry::{lookup_ipv4, SpecialPurposeInfo};
+use crate::ipv4_summary::parse_ipv4_range;
+use crate::ipv4_summary::{mask_for_prefix, range_to_cidrs};
+use crate::public_ip::first_public_ipv4;
 use num_format::{Locale, ToFormattedString};
 use serde::Serialize;
 use std::net::Ipv4Addr;
@@ -24,6 +28,7 @@ pub struct SubnetResult {
     pub in_addr_arpa: String,
     pub ipv4_mapped_address: String,
     pub six_to_four_prefix: String,
+    pub special_purpose: Option<SpecialPurposeInfo>,
 }
 
 #[derive(Serialize)]
@@ -34,102 +39,218 @@ pub struct NetworkEntry {
     pub broadcast: String,
 }
 
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    Cidr,
+    Mask,
+    Wildcard,
+    Classful,
+    Range,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputInterpretation {
+    pub format: InputFormat,
+    pub description: String,
+    pub covering_cidrs: Vec<String>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SubnetComputation {
     pub result: SubnetResult,
     pub related_networks: Vec<NetworkEntry>,
+    pub interpretation: InputInterpretation,
 }
 
 #[tauri::command]
 pub async fn get_public_ip() -> Result<String, String> {
-    // 使用多个公网 IP 服务作为备选，优先使用国内服务
-    let services = vec![
-        // 优先使用 ipip.net
-        "https://myip.ipip.net/", // ipip.net - 专业 IP 服务
-        // 备用纯文本服务
-        "https://api.ip.sb/ip",          // IP.SB - 返回纯文本 IP
-        "https://api.ipify.org",         // ipify - 全球最大，返回纯文本
-        "https://icanhazip.com",         // icanhazip - 返回纯文本
-        "https://ipecho.net/plain",      // ipecho - 返回纯文本
-        "https://checkip.amazonaws.com", // AWS - 返回纯文本
-    ];
-
-    for service in services {
-        match reqwest::get(service).await {
-            Ok(response) => {
-                match response.text().await {
-                    Ok(ip_text) => {
-                        let ip_text = ip_text.trim();
-
-                        // 特殊处理 ipip.net 的格式
-                        if service.contains("ipip.net") {
-                            // 从 "当前 IP：111.205.145.129  来自于：中国 北京 北京  联通" 中提取 IP
-                            if let Some(ip) = extract_ip_from_ipip_text(ip_text) {
-                                return Ok(ip);
-                            }
-                        } else {
-                            // 验证是否是有效的 IPv4 地址
-                            if ip_text.parse::<Ipv4Addr>().is_ok() {
-                                return Ok(ip_text.to_string());
-                            }
-                        }
-                    }
-                    Err(_) => continue,
-                }
-            }
-            Err(_) => continue,
+    // 服务列表可在配置目录的 public_ip_services.yaml 中自定义
+    first_public_ipv4().await
+}
+
+#[tauri::command]
+pub fn compute_subnet(input: &str) -> Result<SubnetComputation, String> {
+    let (ip_addr, cidr, interpretation) = parse_subnet_input(input)?;
+    let result = build_subnet_result(ip_addr, cidr);
+    let related_networks = build_related_networks(u32::from(ip_addr), cidr, &result.ip_class);
+
+    Ok(SubnetComputation {
+        result,
+        related_networks,
+        interpretation,
+    })
+}
+
+// 支持 ip/cidr、ip/掩码、ip 掩码、ip 反掩码、纯 IP（按地址类别取默认掩码）以及 起始-结束 范围，
+// 粘贴的设备配置行（如 ip address ... / rule permit source ...）会跳过前面的关键字
+pub(crate) fn parse_subnet_input(
+    input: &str,
+) -> Result<(Ipv4Addr, u32, InputInterpretation), String> {
+    let normalized = input.trim();
+    if normalized.is_empty() {
+        return Err("请输入合法的 IPv4/CIDR，例如 192.168.0.1/24".to_string());
+    }
+
+    // 范围写法与汇总计算共用解析，支持 10.0.0.1-50 这样的简化写法
+    if let Some((start_part, _)) = normalized.split_once('-') {
+        if start_part.trim().parse::<Ipv4Addr>().is_ok() {
+            let (start, end) = parse_ipv4_range(normalized)?;
+            return parse_range_input(start, end);
         }
     }
 
-    Err("无法获取公网 IP 地址".to_string())
-}
+    let tokens: Vec<&str> = normalized.split_whitespace().collect();
+    let position = tokens
+        .iter()
+        .position(|token| {
+            let ip_part = token.split_once('/').map_or(*token, |(ip, _)| ip);
+            ip_part.parse::<Ipv4Addr>().is_ok()
+        })
+        .ok_or_else(|| "请输入合法的 IPv4/CIDR，例如 192.168.0.1/24".to_string())?;
 
-// 从 ipip.net 的文本中提取 IP 地址
-fn extract_ip_from_ipip_text(text: &str) -> Option<String> {
-    // ipip.net 返回格式类似: "当前 IP：111.205.145.129  来自于：中国 北京 北京  联通"
+    let token = tokens[position];
+    if let Some((ip_part, suffix)) = token.split_once('/') {
+        let ip_addr: Ipv4Addr = ip_part
+            .parse()
+            .map_err(|_| "IPv4 地址格式不正确".to_string())?;
+        if suffix.contains('.') {
+            let mask: Ipv4Addr = suffix
+                .parse()
+                .map_err(|_| "子网掩码格式不正确".to_string())?;
+            let cidr = prefix_from_mask(u32::from(mask))
+                .ok_or_else(|| format!("{} 不是连续的子网掩码", mask))?;
+            return Ok((
+                ip_addr,
+                cidr,
+                interpretation(InputFormat::Mask, format!("子网掩码 {} -> /{}", mask, cidr)),
+            ));
+        }
 
-    // 直接使用备用方法：查找 IPv4 地址模式，更安全
-    for word in text.split_whitespace() {
-        if let Ok(ip) = word.parse::<Ipv4Addr>() {
-            return Some(ip.to_string());
+        let cidr: u32 = suffix
+            .parse()
+            .map_err(|_| "CIDR 必须是 0 到 32 的整数".to_string())?;
+        if cidr > 32 {
+            return Err("CIDR 必须在 0 到 32 之间".to_string());
         }
+        return Ok((
+            ip_addr,
+            cidr,
+            interpretation(InputFormat::Cidr, format!("CIDR /{}", cidr)),
+        ));
     }
 
-    // 如果备用方法失败，尝试正则表达式方法
-    use regex::Regex;
-    if let Ok(re) = Regex::new(r"\b(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})\b") {
-        if let Some(caps) = re.captures(text) {
-            if let Some(ip_match) = caps.get(1) {
-                if let Ok(ip) = ip_match.as_str().parse::<Ipv4Addr>() {
-                    return Some(ip.to_string());
-                }
-            }
+    let ip_addr: Ipv4Addr = token
+        .parse()
+        .map_err(|_| "IPv4 地址格式不正确".to_string())?;
+
+    let next_mask = match tokens.get(position + 1) {
+        // 华为/H3C ACL 中 "source 10.1.1.1 0" 表示单个主机
+        Some(&"0") => Some(Ipv4Addr::UNSPECIFIED),
+        Some(next) => next.parse::<Ipv4Addr>().ok(),
+        None => None,
+    };
+
+    let Some(mask) = next_mask else {
+        let ip_class = derive_class(u32::from(ip_addr));
+        let cidr = class_boundary(&ip_class).unwrap_or(32);
+        return Ok((
+            ip_addr,
+            cidr,
+            interpretation(
+                InputFormat::Classful,
+                format!("未指定掩码，按 {} 类地址默认使用 /{}", ip_class, cidr),
+            ),
+        ));
+    };
+
+    match classify_mask(u32::from(mask)) {
+        Some((cidr, InputFormat::Mask)) => {
+            return Ok((
+                ip_addr,
+                cidr,
+                interpretation(InputFormat::Mask, format!("子网掩码 {} -> /{}", mask, cidr)),
+            ));
         }
+        Some((cidr, format)) => {
+            return Ok((
+                ip_addr,
+                cidr,
+                interpretation(format, format!("反掩码 {} -> /{}", mask, cidr)),
+            ));
+        }
+        None => {}
     }
 
-    None
+    Err(format!(
+        "{} 既不是连续的子网掩码也不是连续的反掩码，可使用反掩码计算工具展开",
+        mask
+    ))
 }
 
-#[tauri::command]
-pub fn compute_subnet(input: &str) -> Result<SubnetComputation, String> {
-    let normalized = input.trim();
-    let (ip_part, cidr_part) = normalized
-        .split_once('/')
-        .ok_or_else(|| "请输入合法的 IPv4/CIDR，例如 192.168.0.1/24".to_string())?;
+// 未注明是掩码还是反掩码时的判定：0.0.0.0 在 ACL 中更常见的含义是主机反掩码，因此优先按反掩码解释
+pub(crate) fn classify_mask(value: u32) -> Option<(u32, InputFormat)> {
+    if value != 0 {
+        if let Some(cidr) = prefix_from_mask(value) {
+            return Some((cidr, InputFormat::Mask));
+        }
+    }
+    prefix_from_mask(!value).map(|cidr| (cidr, InputFormat::Wildcard))
+}
 
-    let ip_addr: Ipv4Addr = ip_part
-        .parse()
-        .map_err(|_| "IPv4 地址格式不正确".to_string())?;
+fn parse_range_input(
+    start: Ipv4Addr,
+    end: Ipv4Addr,
+) -> Result<(Ipv4Addr, u32, InputInterpretation), String> {
+    let start_int = u32::from(start);
+    let end_int = u32::from(end);
+    if end_int < start_int {
+        return Err("结束 IP 必须大于或等于起始 IP".to_string());
+    }
 
-    let cidr: u32 = cidr_part
-        .parse()
-        .map_err(|_| "CIDR 必须是 0 到 32 的整数".to_string())?;
+    // 以覆盖整个范围的最小网段作为计算结果，同时给出精确覆盖的 CIDR 列表
+    let cidr = (start_int ^ end_int).leading_zeros();
+    let network = start_int & mask_for_prefix(cidr);
+    let covering_cidrs = range_to_cidrs(start_int, end_int);
+
+    Ok((
+        Ipv4Addr::from(network),
+        cidr,
+        InputInterpretation {
+            format: InputFormat::Range,
+            description: format!(
+                "范围 {} - {}，最小覆盖网段 {}/{}，精确覆盖需要 {} 个 CIDR",
+                start,
+                end,
+                Ipv4Addr::from(network),
+                cidr,
+                covering_cidrs.len()
+            ),
+            covering_cidrs,
+        },
+    ))
+}
 
-    if cidr > 32 {
-        return Err("CIDR 必须在 0 到 32 之间".to_string());
+fn interpretation(format: InputFormat, description: String) -> InputInterpretation {
+    InputInterpretation {
+        format,
+        description,
+        covering_cidrs: Vec::new(),
     }
+}
+
+pub(crate) fn prefix_from_mask(mask: u32) -> Option<u32> {
+    let prefix = mask.leading_ones();
+    if mask_for_prefix(prefix) == mask {
+        Some(prefix)
+    } else {
+        None
+    }
+}
 
+pub(crate) fn build_subnet_result(ip_addr: Ipv4Addr, cidr: u32) -> SubnetResult {
     let ip_int = u32::from(ip_addr);
     let mask = if cidr == 0 {
         0
@@ -162,9 +283,14 @@ pub fn compute_subnet(input: &str) -> Result<SubnetComputation, String> {
     };
 
     let ip_class = derive_class(ip_int);
-    let ip_type = derive_type(ip_int, &ip_class);
+    let special_purpose = lookup_ipv4(ip_int);
+    // 优先使用 IANA 特殊用途地址注册表中的名称，未命中时按地址段推断
+    let ip_type = match &special_purpose {
+        Some(info) => info.name.clone(),
+        None => derive_type(ip_int, &ip_class),
+    };
 
-    let result = SubnetResult {
+    SubnetResult {
         ip_address: ip_addr.to_string(),
         network_address: format_ip(network),
         broadcast_address: format_ip(broadcast),
@@ -184,14 +310,8 @@ pub fn compute_subnet(input: &str) -> Result<SubnetComputation, String> {
         in_addr_arpa: build_in_addr_arpa(ip_addr),
         ipv4_mapped_address: build_ipv4_mapped(ip_int),
         six_to_four_prefix: build_six_to_four_prefix(ip_int),
-    };
-
-    let related_networks = build_related_networks(ip_int, cidr, &ip_class);
-
-    Ok(SubnetComputation {
-        result,
-        related_networks,
-    })
+        special_purpose,
+    }
 }
 
 fn build_related_networks(ip_int: u32, cidr: u32, ip_class: &str) -> Vec<NetworkEntry> {
@@ -335,3 +455,71 @@ fn build_six_to_four_prefix(ip_int: u32) -> String {
         ip_int & 0xffff
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> (String, u32, InputFormat) {
+        let (ip, cidr, interpretation) = parse_subnet_input(input).expect("should parse");
+        (ip.to_string(), cidr, interpretation.format)
+    }
+
+    #[test]
+    fn parses_device_config_forms() {
+        assert_eq!(
+            parse("ip address 10.1.1.1 255.255.255.0"),
+            ("10.1.1.1".to_string(), 24, InputFormat::Mask)
+        );
+        assert_eq!(
+            parse("rule 5 permit source 10.1.1.0 0.0.0.255"),
+            ("10.1.1.0".to_string(), 24, InputFormat::Wildcard)
+        );
+        assert_eq!(
+            parse("rule permit source 10.1.1.1 0"),
+            ("10.1.1.1".to_string(), 32, InputFormat::Wildcard)
+        );
+        assert_eq!(
+            parse("172.16.5.4"),
+            ("172.16.5.4".to_string(), 16, InputFormat::Classful)
+        );
+        assert_eq!(
+            parse("192.168.1.1/255.255.255.128"),
+            ("192.168.1.1".to_string(), 25, InputFormat::Mask)
+        );
+        assert!(parse_subnet_input("10.0.0.0 0.0.254.255").is_err());
+    }
+
+    #[test]
+    fn parses_range_with_covering_cidrs() {
+        let (ip, cidr, interpretation) =
+            parse_subnet_input("10.0.0.1 - 10.0.0.6").expect("should parse");
+        assert_eq!(ip.to_string(), "10.0.0.0");
+        assert_eq!(cidr, 29);
+        assert_eq!(
+            interpretation.covering_cidrs,
+            vec!["10.0.0.1/32", "10.0.0.2/31", "10.0.0.4/31", "10.0.0.6/32"]
+        );
+
+        let (ip, cidr, interpretation) = parse_subnet_input("10.0.0.1-6").expect("should parse");
+        assert_eq!(ip.to_string(), "10.0.0.0");
+        assert_eq!(cidr, 29);
+        assert_eq!(interpretation.covering_cidrs.len(), 4);
+        assert!(parse_subnet_input("10.0.0.9-3").is_err());
+    }
+
+    #[test]
+    fn labels_type_from_special_purpose_registry() {
+        let result = build_subnet_result(Ipv4Addr::new(10, 1, 1, 1), 24);
+        assert_eq!(result.ip_type, "Private-Use");
+
+        let documentation = build_subnet_result(Ipv4Addr::new(192, 0, 2, 1), 24);
+        assert_eq!(documentation.ip_type, "Documentation (TEST-NET-1)");
+
+        let benchmarking = build_subnet_result(Ipv4Addr::new(198, 18, 0, 1), 15);
+        assert_eq!(benchmarking.ip_type, "Benchmarking");
+
+        let public = build_subnet_result(Ipv4Addr::new(8, 8, 8, 8), 24);
+        assert_eq!(public.ip_type, "Public");
+    }
+}
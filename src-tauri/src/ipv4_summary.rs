@@ -27,6 +27,46 @@ pub struct NonPreciseSummary {
     pub extra_percentage: f64,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv4ExcludeResult {
+    pub supernet: String,
+    pub normalized_exclusions: Vec<String>,
+    pub remaining: Vec<String>,
+    pub remaining_addresses: u64,
+    pub excluded_addresses: u64,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv4OverlapEntry {
+    pub a_line: usize,
+    pub a_entry: String,
+    pub b_line: usize,
+    pub b_entry: String,
+    pub overlap: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv4CompareResult {
+    pub normalized_a: Vec<String>,
+    pub normalized_b: Vec<String>,
+    pub union: Vec<String>,
+    pub intersection: Vec<String>,
+    pub a_minus_b: Vec<String>,
+    pub b_minus_a: Vec<String>,
+    pub overlaps: Vec<Ipv4OverlapEntry>,
+    pub errors: Vec<String>,
+}
+
+struct ParsedItem {
+    line: usize,
+    range: Range,
+    canonical: String,
+}
+
 #[tauri::command]
 pub fn aggregate_ipv4(items: Vec<String>) -> Result<Ipv4AggregateResult, String> {
     let mut ranges: Vec<Range> = Vec::new();
@@ -75,6 +115,167 @@ pub fn aggregate_ipv4(items: Vec<String>) -> Result<Ipv4AggregateResult, String>
     })
 }
 
+#[tauri::command]
+pub fn exclude_ipv4(
+    supernet: String,
+    exclusions: Vec<String>,
+) -> Result<Ipv4ExcludeResult, String> {
+    let trimmed = supernet.trim();
+    if trimmed.is_empty() {
+        return Err("请输入需要排除地址的父网段".into());
+    }
+    let (base, canonical_supernet) =
+        parse_entry(trimmed).map_err(|err| format!("{} -> {}", trimmed, err))?;
+
+    let mut removed: Vec<Range> = Vec::new();
+    let mut normalized_exclusions: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+
+    for raw in exclusions {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match parse_entry(trimmed) {
+            Ok((range, canonical)) => {
+                removed.push(range);
+                normalized_exclusions.push(canonical);
+            }
+            Err(err) => {
+                errors.push(format!("{} -> {}", trimmed, err));
+            }
+        }
+    }
+
+    removed.sort_by_key(|r| r.start);
+    let removed = merge_ranges(removed);
+    let remaining_ranges = subtract_ranges(&[base], &removed);
+
+    let mut remaining: Vec<String> = Vec::new();
+    for range in &remaining_ranges {
+        remaining.extend(range_to_cidrs(range.start, range.end));
+    }
+
+    let remaining_addresses = count_addresses(&remaining_ranges);
+    let excluded_addresses = count_addresses(&[base]) - remaining_addresses;
+
+    Ok(Ipv4ExcludeResult {
+        supernet: canonical_supernet,
+        normalized_exclusions,
+        remaining,
+        remaining_addresses,
+        excluded_addresses,
+        errors,
+    })
+}
+
+#[tauri::command]
+pub fn compare_ipv4_lists(
+    list_a: Vec<String>,
+    list_b: Vec<String>,
+) -> Result<Ipv4CompareResult, String> {
+    let mut errors: Vec<String> = Vec::new();
+    let items_a = parse_items(&list_a, "A", &mut errors);
+    let items_b = parse_items(&list_b, "B", &mut errors);
+
+    if items_a.is_empty() && items_b.is_empty() {
+        return Err("没有可比较的 IPv4 数据".into());
+    }
+
+    let merged_a = merge_items(&items_a);
+    let merged_b = merge_items(&items_b);
+
+    let mut all = merged_a.clone();
+    all.extend(merged_b.iter().copied());
+    all.sort_by_key(|r| r.start);
+    let union = merge_ranges(all);
+
+    let intersection = intersect_ranges(&merged_a, &merged_b);
+    let a_minus_b = subtract_ranges(&merged_a, &merged_b);
+    let b_minus_a = subtract_ranges(&merged_b, &merged_a);
+
+    let mut sorted_b: Vec<&ParsedItem> = items_b.iter().collect();
+    sorted_b.sort_by_key(|item| item.range.start);
+
+    let mut overlaps: Vec<Ipv4OverlapEntry> = Vec::new();
+    for a in &items_a {
+        for b in &sorted_b {
+            if b.range.start > a.range.end {
+                break;
+            }
+            if b.range.end < a.range.start {
+                continue;
+            }
+            let start = a.range.start.max(b.range.start);
+            let end = a.range.end.min(b.range.end);
+            overlaps.push(Ipv4OverlapEntry {
+                a_line: a.line,
+                a_entry: a.canonical.clone(),
+                b_line: b.line,
+                b_entry: b.canonical.clone(),
+                overlap: range_to_cidrs(start, end),
+            });
+        }
+    }
+
+    Ok(Ipv4CompareResult {
+        normalized_a: items_a.iter().map(|item| item.canonical.clone()).collect(),
+        normalized_b: items_b.iter().map(|item| item.canonical.clone()).collect(),
+        union: ranges_to_cidrs(&union),
+        intersection: ranges_to_cidrs(&intersection),
+        a_minus_b: ranges_to_cidrs(&a_minus_b),
+        b_minus_a: ranges_to_cidrs(&b_minus_a),
+        overlaps,
+        errors,
+    })
+}
+
+fn parse_items(items: &[String], label: &str, errors: &mut Vec<String>) -> Vec<ParsedItem> {
+    let mut parsed = Vec::new();
+    for (index, raw) in items.iter().enumerate() {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match parse_entry(trimmed) {
+            Ok((range, canonical)) => parsed.push(ParsedItem {
+                line: index + 1,
+                range,
+                canonical,
+            }),
+            Err(err) => {
+                let location = if label.is_empty() {
+                    format!("第 {} 行", index + 1)
+                } else {
+                    format!("{} 第 {} 行", label, index + 1)
+                };
+                errors.push(format!("{}: {} -> {}", location, trimmed, err));
+            }
+        }
+    }
+    parsed
+}
+
+// 解析并合并输入，返回互不重叠的 (网络地址, 前缀长度) 列表、规范化输入和错误信息
+pub(crate) fn parse_to_prefixes(items: &[String]) -> (Vec<(u32, u32)>, Vec<String>, Vec<String>) {
+    let mut errors: Vec<String> = Vec::new();
+    let parsed = parse_items(items, "", &mut errors);
+    let normalized = parsed.iter().map(|item| item.canonical.clone()).collect();
+    let prefixes = merge_items(&parsed)
+        .iter()
+        .flat_map(|range| range_to_prefixes(range.start, range.end))
+        .collect();
+    (prefixes, normalized, errors)
+}
+
+fn merge_items(items: &[ParsedItem]) -> Vec<Range> {
+    let mut ranges: Vec<Range> = items.iter().map(|item| item.range).collect();
+    ranges.sort_by_key(|r| r.start);
+    merge_ranges(ranges)
+}
+
 fn parse_entry(entry: &str) -> Result<(Range, String), String> {
     if entry.contains('/') {
         parse_cidr(entry)
@@ -112,6 +313,18 @@ fn parse_cidr(entry: &str) -> Result<(Range, String), String> {
 }
 
 fn parse_range(entry: &str) -> Result<(Range, String), String> {
+    let (start_ip, end_ip) = parse_ipv4_range(entry)?;
+    Ok((
+        Range {
+            start: ipv4_to_u32(start_ip),
+            end: ipv4_to_u32(end_ip),
+        },
+        format!("{} - {}", start_ip, end_ip),
+    ))
+}
+
+// 完整写法 10.0.0.1-10.0.0.50，或只写末段的简化写法 10.0.0.1-50
+pub(crate) fn parse_ipv4_range(entry: &str) -> Result<(Ipv4Addr, Ipv4Addr), String> {
     let parts: Vec<&str> = entry.split('-').collect();
     if parts.len() != 2 {
         return Err("范围格式不正确".into());
@@ -137,18 +350,11 @@ fn parse_range(entry: &str) -> Result<(Range, String), String> {
         Ipv4Addr::from(octets)
     };
 
-    let end_u32 = ipv4_to_u32(end_ip);
-    if end_u32 < start_u32 {
+    if ipv4_to_u32(end_ip) < start_u32 {
         return Err("结束 IP 必须大于或等于起始 IP".into());
     }
 
-    Ok((
-        Range {
-            start: start_u32,
-            end: end_u32,
-        },
-        format!("{} - {}", start_ip, end_ip),
-    ))
+    Ok((start_ip, end_ip))
 }
 
 fn parse_single(entry: &str) -> Result<(Range, String), String> {
@@ -187,7 +393,90 @@ fn merge_ranges(ranges: Vec<Range>) -> Vec<Range> {
     merged
 }
 
-fn range_to_cidrs(start: u32, end: u32) -> Vec<String> {
+// 两个输入均需已排序且已合并，返回 base 中不被 removed 覆盖的部分
+fn subtract_ranges(base: &[Range], removed: &[Range]) -> Vec<Range> {
+    let mut result: Vec<Range> = Vec::new();
+    let mut removed_iter = removed.iter().peekable();
+
+    for range in base {
+        let mut current_start = range.start as u64;
+        let end = range.end as u64;
+
+        while let Some(cut) = removed_iter.peek() {
+            if (cut.end as u64) < current_start {
+                removed_iter.next();
+                continue;
+            }
+            if cut.start as u64 > end {
+                break;
+            }
+            if (cut.start as u64) > current_start {
+                result.push(Range {
+                    start: current_start as u32,
+                    end: cut.start - 1,
+                });
+            }
+            current_start = cut.end as u64 + 1;
+            if current_start > end {
+                break;
+            }
+            removed_iter.next();
+        }
+
+        if current_start <= end {
+            result.push(Range {
+                start: current_start as u32,
+                end: range.end,
+            });
+        }
+    }
+
+    result
+}
+
+// 两个输入均需已排序且已合并
+fn intersect_ranges(a: &[Range], b: &[Range]) -> Vec<Range> {
+    let mut result: Vec<Range> = Vec::new();
+    let (mut i, mut j) = (0usize, 0usize);
+
+    while i < a.len() && j < b.len() {
+        let start = a[i].start.max(b[j].start);
+        let end = a[i].end.min(b[j].end);
+        if start <= end {
+            result.push(Range { start, end });
+        }
+        if a[i].end < b[j].end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    result
+}
+
+fn ranges_to_cidrs(ranges: &[Range]) -> Vec<String> {
+    ranges
+        .iter()
+        .flat_map(|range| range_to_cidrs(range.start, range.end))
+        .collect()
+}
+
+fn count_addresses(ranges: &[Range]) -> u64 {
+    ranges
+        .iter()
+        .map(|range| (range.end as u64) - (range.start as u64) + 1)
+        .sum()
+}
+
+pub(crate) fn range_to_cidrs(start: u32, end: u32) -> Vec<String> {
+    range_to_prefixes(start, end)
+        .into_iter()
+        .map(|(network, prefix)| format!("{}/{}", Ipv4Addr::from(network), prefix))
+        .collect()
+}
+
+fn range_to_prefixes(start: u32, end: u32) -> Vec<(u32, u32)> {
     let mut result = Vec::new();
     let mut current = start as u64;
     let end64 = end as u64;
@@ -207,7 +496,7 @@ fn range_to_cidrs(start: u32, end: u32) -> Vec<String> {
             block_size >>= 1;
         }
 
-        result.push(format!("{}/{}", Ipv4Addr::from(cur_u32), prefix));
+        result.push((cur_u32, prefix));
         current += block_size;
     }
     result
@@ -291,10 +580,71 @@ fn ipv4_to_u32(ip: Ipv4Addr) -> u32 {
     u32::from(ip)
 }
 
-fn mask_for_prefix(prefix: u32) -> u32 {
+pub(crate) fn mask_for_prefix(prefix: u32) -> u32 {
     if prefix == 0 {
         0
     } else {
         u32::MAX << (32 - prefix)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_lists_reports_set_operations_and_overlaps() {
+        let result = compare_ipv4_lists(
+            vec!["10.0.0.0/24".into(), "10.0.2.1".into()],
+            vec!["10.0.0.128-10.0.1.255".into(), "192.168.0.1".into()],
+        )
+        .expect("should compare");
+
+        assert_eq!(
+            result.union,
+            vec!["10.0.0.0/23", "10.0.2.1/32", "192.168.0.1/32"]
+        );
+        assert_eq!(result.intersection, vec!["10.0.0.128/25"]);
+        assert_eq!(result.a_minus_b, vec!["10.0.0.0/25", "10.0.2.1/32"]);
+        assert_eq!(result.b_minus_a, vec!["10.0.1.0/24", "192.168.0.1/32"]);
+        assert_eq!(result.overlaps.len(), 1);
+        assert_eq!(result.overlaps[0].a_line, 1);
+        assert_eq!(result.overlaps[0].b_line, 1);
+    }
+
+    #[test]
+    fn exclude_carves_out_private_space() {
+        let result = exclude_ipv4(
+            "0.0.0.0/0".into(),
+            vec![
+                "10.0.0.0/8".into(),
+                "172.16.0.0/12".into(),
+                "192.168.0.0/16".into(),
+            ],
+        )
+        .expect("should compute");
+
+        assert!(result.errors.is_empty());
+        assert!(result.remaining.contains(&"0.0.0.0/5".to_string()));
+        assert!(result.remaining.contains(&"11.0.0.0/8".to_string()));
+        assert!(!result.remaining.iter().any(|cidr| cidr.starts_with("10.")));
+        assert_eq!(result.excluded_addresses, (1 << 24) + (1 << 20) + (1 << 16));
+    }
+
+    #[test]
+    fn exclude_handles_partial_and_outside_ranges() {
+        let result = exclude_ipv4(
+            "192.168.1.0/24".into(),
+            vec![
+                "192.168.1.0-127".into(),
+                "192.168.0.0/24".into(),
+                "bad".into(),
+            ],
+        )
+        .expect("should compute");
+
+        assert_eq!(result.remaining, vec!["192.168.1.128/25".to_string()]);
+        assert_eq!(result.remaining_addresses, 128);
+        assert_eq!(result.errors.len(), 1);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::ipv4_summary::{mask_for_prefix, range_to_cidrs};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VlsmRequirement {
+    pub name: String,
+    pub hosts: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VlsmRequest {
+    pub parent: String,
+    pub requirements: Vec<VlsmRequirement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VlsmAllocation {
+    pub name: String,
+    pub requested_hosts: u32,
+    pub usable_hosts: u64,
+    pub network: String,
+    pub subnet_mask: String,
+    pub wildcard_mask: String,
+    pub usable_range: String,
+    pub broadcast: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VlsmPlanResult {
+    pub parent: String,
+    pub allocations: Vec<VlsmAllocation>,
+    pub free_blocks: Vec<String>,
+    pub total_addresses: u64,
+    pub allocated_addresses: u64,
+    pub free_addresses: u64,
+    pub utilization_percentage: f64,
+}
+
+#[tauri::command]
+pub fn plan_vlsm(request: VlsmRequest) -> Result<VlsmPlanResult, String> {
+    let (parent_network, parent_prefix) = parse_parent(&request.parent)?;
+    let parent_size = block_size(parent_prefix);
+    let parent_end = (parent_network as u64 + parent_size - 1) as u32;
+
+    if request.requirements.is_empty() {
+        return Err("请至少填写一个子网需求".into());
+    }
+
+    let mut sized: Vec<(usize, u32, &VlsmRequirement)> = Vec::new();
+    for (index, requirement) in request.requirements.iter().enumerate() {
+        let name = requirement.name.trim();
+        if name.is_empty() {
+            return Err(format!("第 {} 个需求缺少名称", index + 1));
+        }
+        if requirement.hosts == 0 {
+            return Err(format!("{} 的主机数必须大于 0", name));
+        }
+        let prefix = prefix_for_hosts(requirement.hosts);
+        if prefix < parent_prefix {
+            return Err(format!(
+                "{} 需要 /{} 子网，超出父网段 /{} 的容量",
+                name, prefix, parent_prefix
+            ));
+        }
+        sized.push((index, prefix, requirement));
+    }
+
+    // 按块大小从大到小排序，相同大小保持输入顺序，保证顺序分配时天然对齐
+    sized.sort_by_key(|(index, prefix, _)| (*prefix, *index));
+
+    let demanded: u64 = sized.iter().map(|(_, prefix, _)| block_size(*prefix)).sum();
+    if demanded > parent_size {
+        return Err(format!(
+            "地址需求 {} 超出父网段 {}/{} 的容量 {}，缺少 {} 个地址",
+            demanded,
+            Ipv4Addr::from(parent_network),
+            parent_prefix,
+            parent_size,
+            demanded - parent_size
+        ));
+    }
+
+    let mut cursor = parent_network as u64;
+    let mut allocations = Vec::with_capacity(sized.len());
+    for (_, prefix, requirement) in sized {
+        let network = cursor as u32;
+        allocations.push(build_allocation(requirement, network, prefix));
+        cursor += block_size(prefix);
+    }
+
+    let free_blocks = if cursor <= parent_end as u64 {
+        range_to_cidrs(cursor as u32, parent_end)
+    } else {
+        Vec::new()
+    };
+
+    let free_addresses = parent_size - demanded;
+    let utilization_percentage = (demanded as f64 / parent_size as f64) * 100.0;
+
+    Ok(VlsmPlanResult {
+        parent: format!("{}/{}", Ipv4Addr::from(parent_network), parent_prefix),
+        allocations,
+        free_blocks,
+        total_addresses: parent_size,
+        allocated_addresses: demanded,
+        free_addresses,
+        utilization_percentage,
+    })
+}
+
+fn parse_parent(input: &str) -> Result<(u32, u32), String> {
+    let (ip_part, prefix_part) = input
+        .trim()
+        .split_once('/')
+        .ok_or_else(|| "请输入合法的父网段，例如 10.20.0.0/16".to_string())?;
+
+    let ip: Ipv4Addr = ip_part
+        .trim()
+        .parse()
+        .map_err(|_| "父网段 IPv4 地址格式不正确".to_string())?;
+    let prefix: u32 = prefix_part
+        .trim()
+        .parse()
+        .map_err(|_| "CIDR 必须是 0 到 32 的整数".to_string())?;
+    if prefix > 32 {
+        return Err("CIDR 必须在 0 到 32 之间".into());
+    }
+
+    Ok((u32::from(ip) & mask_for_prefix(prefix), prefix))
+}
+
+// 与 compute_subnet 的可用主机口径保持一致：/32 为 1 个，/31 为 2 个
+fn prefix_for_hosts(hosts: u32) -> u32 {
+    match hosts {
+        1 => 32,
+        2 => 31,
+        _ => {
+            let needed = hosts as u64 + 2;
+            let bits = 64 - (needed - 1).leading_zeros();
+            32u32.saturating_sub(bits)
+        }
+    }
+}
+
+fn build_allocation(requirement: &VlsmRequirement, network: u32, prefix: u32) -> VlsmAllocation {
+    let mask = mask_for_prefix(prefix);
+    let broadcast = network | !mask;
+
+    let (usable_hosts, first, last) = match prefix {
+        32 => (1u64, network, network),
+        31 => (2u64, network, broadcast),
+        _ => (block_size(prefix) - 2, network + 1, broadcast - 1),
+    };
+
+    let usable_range = if prefix == 32 {
+        Ipv4Addr::from(first).to_string()
+    } else {
+        format!("{} - {}", Ipv4Addr::from(first), Ipv4Addr::from(last))
+    };
+
+    VlsmAllocation {
+        name: requirement.name.trim().to_string(),
+        requested_hosts: requirement.hosts,
+        usable_hosts,
+        network: format!("{}/{}", Ipv4Addr::from(network), prefix),
+        subnet_mask: Ipv4Addr::from(mask).to_string(),
+        wildcard_mask: Ipv4Addr::from(!mask).to_string(),
+        usable_range,
+        broadcast: Ipv4Addr::from(broadcast).to_string(),
+    }
+}
+
+fn block_size(prefix: u32) -> u64 {
+    1u64 << (32 - prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirement(name: &str, hosts: u32) -> VlsmRequirement {
+        VlsmRequirement {
+            name: name.to_string(),
+            hosts,
+        }
+    }
+
+    #[test]
+    fn prefix_for_hosts_matches_usable_counts() {
+        assert_eq!(prefix_for_hosts(1), 32);
+        assert_eq!(prefix_for_hosts(2), 31);
+        assert_eq!(prefix_for_hosts(3), 29);
+        assert_eq!(prefix_for_hosts(6), 29);
+        assert_eq!(prefix_for_hosts(254), 24);
+        assert_eq!(prefix_for_hosts(255), 23);
+        assert_eq!(prefix_for_hosts(500), 23);
+    }
+
+    #[test]
+    fn plan_packs_largest_first() {
+        let result = plan_vlsm(VlsmRequest {
+            parent: "10.20.0.0/16".into(),
+            requirements: vec![
+                requirement("P2P-1", 2),
+                requirement("Office", 500),
+                requirement("Lab", 100),
+            ],
+        })
+        .expect("should fit");
+
+        let networks: Vec<&str> = result
+            .allocations
+            .iter()
+            .map(|a| a.network.as_str())
+            .collect();
+        assert_eq!(networks, ["10.20.0.0/23", "10.20.2.0/25", "10.20.2.128/31"]);
+        assert_eq!(result.allocations[0].name, "Office");
+        assert_eq!(
+            result.free_blocks.first().map(String::as_str),
+            Some("10.20.2.130/31")
+        );
+        assert_eq!(result.free_addresses, 65536 - 512 - 128 - 2);
+    }
+
+    #[test]
+    fn plan_rejects_oversized_demand() {
+        let result = plan_vlsm(VlsmRequest {
+            parent: "192.168.1.0/24".into(),
+            requirements: vec![requirement("A", 200), requirement("B", 100)],
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,491 @@
+use crate::ipv4_summary::parse_to_prefixes;
+use serde::Serialize;
+use std::net::Ipv4Addr;
+
+const MAX_INPUT_PREFIXES: usize = 512;
+const MAX_EXPANDED_NETWORKS: u64 = 4096;
+// 精确覆盖的规模上限，超出时保留贪心结果
+const MAX_COVER_ELEMENTS: usize = 4096;
+const MAX_SEARCH_NODES: usize = 200_000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WildcardEntry {
+    pub address: String,
+    pub wildcard: String,
+    pub contiguous: bool,
+    pub matched_addresses: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WildcardMatchResult {
+    pub normalized_inputs: Vec<String>,
+    pub entries: Vec<WildcardEntry>,
+    pub input_prefix_count: usize,
+    pub total_addresses: u64,
+    // 是否确认为条目数最少的组合；规模超限时为贪心结果
+    pub minimal: bool,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WildcardExpandResult {
+    pub address: String,
+    pub wildcard: String,
+    pub contiguous: bool,
+    pub networks: Vec<String>,
+    pub total_networks: u64,
+    pub total_addresses: u64,
+    pub truncated: bool,
+}
+
+// 以 (地址, 通配符) 表示的立方体，通配符为 1 的位表示任意
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Cube {
+    value: u32,
+    wildcard: u32,
+}
+
+impl Cube {
+    fn contains(&self, other: &Cube) -> bool {
+        other.wildcard & !self.wildcard == 0 && other.value & !self.wildcard == self.value
+    }
+
+    fn size(&self) -> u64 {
+        1u64 << self.wildcard.count_ones()
+    }
+
+    fn overlaps(&self, other: &Cube) -> bool {
+        (self.value ^ other.value) & !self.wildcard & !other.wildcard == 0
+    }
+
+    // 两个立方体仅在一个双方都确定的位上取值相反时，合并该位得到共识项
+    fn consensus(&self, other: &Cube) -> Option<Cube> {
+        let conflict = (self.value ^ other.value) & !self.wildcard & !other.wildcard;
+        if conflict.count_ones() != 1 {
+            return None;
+        }
+        let wildcard = (self.wildcard & other.wildcard) | conflict;
+        Some(Cube {
+            value: (self.value | other.value) & !wildcard,
+            wildcard,
+        })
+    }
+}
+
+#[tauri::command]
+pub fn compute_wildcard_match(networks: Vec<String>) -> Result<WildcardMatchResult, String> {
+    let (prefixes, normalized_inputs, errors) = parse_to_prefixes(&networks);
+
+    if prefixes.is_empty() {
+        return Err("没有可计算的 IPv4 网段".into());
+    }
+    if prefixes.len() > MAX_INPUT_PREFIXES {
+        return Err(format!(
+            "合并后的网段数量为 {}，超过上限 {}",
+            prefixes.len(),
+            MAX_INPUT_PREFIXES
+        ));
+    }
+
+    let inputs: Vec<Cube> = prefixes
+        .iter()
+        .map(|(network, prefix)| Cube {
+            value: *network,
+            wildcard: wildcard_for_prefix(*prefix),
+        })
+        .collect();
+
+    let primes = prime_implicants(&inputs);
+    let (mut selected, minimal) = select_cover(&inputs, &primes);
+    selected.sort_by_key(|cube| (cube.value, cube.wildcard));
+
+    let total_addresses = inputs.iter().map(Cube::size).sum();
+    let entries = selected
+        .iter()
+        .map(|cube| WildcardEntry {
+            address: Ipv4Addr::from(cube.value).to_string(),
+            wildcard: Ipv4Addr::from(cube.wildcard).to_string(),
+            contiguous: is_contiguous(cube.wildcard),
+            matched_addresses: cube.size(),
+        })
+        .collect();
+
+    Ok(WildcardMatchResult {
+        normalized_inputs,
+        entries,
+        input_prefix_count: inputs.len(),
+        total_addresses,
+        minimal,
+        errors,
+    })
+}
+
+#[tauri::command]
+pub fn expand_wildcard(input: &str) -> Result<WildcardExpandResult, String> {
+    let parts: Vec<&str> = input.split_whitespace().collect();
+    if parts.len() != 2 {
+        return Err("请输入 地址 通配符，例如 10.0.0.0 0.0.254.255".into());
+    }
+
+    let address: Ipv4Addr = parts[0]
+        .parse()
+        .map_err(|_| "IPv4 地址格式不正确".to_string())?;
+    let wildcard: Ipv4Addr = parts[1]
+        .parse()
+        .map_err(|_| "通配符掩码格式不正确".to_string())?;
+
+    let wildcard = u32::from(wildcard);
+    let value = u32::from(address) & !wildcard;
+
+    // 末尾连续的通配位构成每个网段的主机部分，其余通配位需要逐一展开
+    let host_bits = wildcard.trailing_ones();
+    let host_mask = if host_bits == 32 {
+        u32::MAX
+    } else {
+        (1u32 << host_bits) - 1
+    };
+    let spread_mask = wildcard & !host_mask;
+    let spread_bits: Vec<u32> = (0..32)
+        .filter(|bit| spread_mask & (1 << bit) != 0)
+        .collect();
+
+    let total_networks = 1u64 << spread_bits.len();
+    let total_addresses = 1u64 << wildcard.count_ones();
+    let prefix = 32 - host_bits;
+
+    let networks = (0..total_networks.min(MAX_EXPANDED_NETWORKS))
+        .map(|combination| {
+            let mut network = value;
+            for (index, bit) in spread_bits.iter().enumerate() {
+                if combination & (1 << index) != 0 {
+                    network |= 1 << bit;
+                }
+            }
+            format!("{}/{}", Ipv4Addr::from(network), prefix)
+        })
+        .collect();
+
+    Ok(WildcardExpandResult {
+        address: Ipv4Addr::from(value).to_string(),
+        wildcard: Ipv4Addr::from(wildcard).to_string(),
+        contiguous: spread_mask == 0,
+        networks,
+        total_networks,
+        total_addresses,
+        truncated: total_networks > MAX_EXPANDED_NETWORKS,
+    })
+}
+
+// 迭代共识：不断加入共识项并去掉被包含的立方体，直到不再变化，剩下的即全部质蕴含项。
+// 与只合并同尺寸立方体的 Quine-McCluskey 不同，不同前缀长度的网段之间也能合并
+fn prime_implicants(inputs: &[Cube]) -> Vec<Cube> {
+    let mut cubes: Vec<Cube> = Vec::new();
+    for input in inputs {
+        insert_absorbing(&mut cubes, *input);
+    }
+
+    loop {
+        let mut found: Vec<Cube> = Vec::new();
+        for (index, left) in cubes.iter().enumerate() {
+            for right in &cubes[index + 1..] {
+                let Some(cube) = left.consensus(right) else {
+                    continue;
+                };
+                if !cubes
+                    .iter()
+                    .chain(&found)
+                    .any(|cube_in| cube_in.contains(&cube))
+                {
+                    found.push(cube);
+                }
+            }
+        }
+        if found.is_empty() {
+            break;
+        }
+        for cube in found {
+            insert_absorbing(&mut cubes, cube);
+        }
+    }
+
+    cubes.sort_by_key(|cube| (cube.value, cube.wildcard));
+    cubes
+}
+
+fn insert_absorbing(cubes: &mut Vec<Cube>, cube: Cube) {
+    if cubes.iter().any(|existing| existing.contains(&cube)) {
+        return;
+    }
+    cubes.retain(|existing| !cube.contains(existing));
+    cubes.push(cube);
+}
+
+// 把输入网段按质蕴含项的边界切分，使每个片段对任一质蕴含项要么完全包含要么不相交；
+// 片段过多时返回 None
+fn split_elements(inputs: &[Cube], primes: &[Cube]) -> Option<Vec<Cube>> {
+    let mut pending = inputs.to_vec();
+    let mut elements = Vec::new();
+    while let Some(element) = pending.pop() {
+        let partial = primes
+            .iter()
+            .find(|prime| prime.overlaps(&element) && !prime.contains(&element));
+        match partial {
+            Some(prime) => {
+                let split = element.wildcard & !prime.wildcard;
+                let flag = 1u32 << (31 - split.leading_zeros());
+                let half = Cube {
+                    value: element.value,
+                    wildcard: element.wildcard & !flag,
+                };
+                pending.push(half);
+                pending.push(Cube {
+                    value: element.value | flag,
+                    ..half
+                });
+            }
+            None => elements.push(element),
+        }
+        if elements.len() + pending.len() > MAX_COVER_ELEMENTS {
+            return None;
+        }
+    }
+    Some(elements)
+}
+
+// 先以必要质蕴含项 + 贪心得到一个覆盖，再用分支限界搜索条目更少的组合。
+// 返回的布尔值表示结果是否已确认最少
+fn select_cover(inputs: &[Cube], primes: &[Cube]) -> (Vec<Cube>, bool) {
+    let (elements, exact_elements) = match split_elements(inputs, primes) {
+        Some(elements) => (elements, true),
+        None => (inputs.to_vec(), false),
+    };
+    let coverage: Vec<Vec<usize>> = primes
+        .iter()
+        .map(|prime| {
+            elements
+                .iter()
+                .enumerate()
+                .filter(|(_, element)| prime.contains(element))
+                .map(|(index, _)| index)
+                .collect()
+        })
+        .collect();
+
+    let greedy = greedy_cover(elements.len(), primes, &coverage);
+    let mut search = CoverSearch {
+        candidates: (0..elements.len())
+            .map(|element| {
+                (0..primes.len())
+                    .filter(|prime| coverage[*prime].contains(&element))
+                    .collect()
+            })
+            .collect(),
+        coverage: &coverage,
+        best: greedy,
+        nodes: 0,
+    };
+    let mut covered = vec![0usize; elements.len()];
+    search.run(&mut Vec::new(), &mut covered);
+    let minimal = exact_elements && search.nodes <= MAX_SEARCH_NODES;
+
+    let chosen = search.best.into_iter().map(|index| primes[index]).collect();
+    (chosen, minimal)
+}
+
+// 先选必要质蕴含项，再按覆盖未匹配片段最多（同等时匹配范围更大）贪心补齐
+fn greedy_cover(element_count: usize, primes: &[Cube], coverage: &[Vec<usize>]) -> Vec<usize> {
+    let mut covered = vec![false; element_count];
+    let mut chosen: Vec<usize> = Vec::new();
+
+    for element in 0..element_count {
+        let candidates: Vec<usize> = coverage
+            .iter()
+            .enumerate()
+            .filter(|(_, covers)| covers.contains(&element))
+            .map(|(prime_index, _)| prime_index)
+            .collect();
+        if let [only] = candidates.as_slice() {
+            if !chosen.contains(only) {
+                chosen.push(*only);
+                for &index in &coverage[*only] {
+                    covered[index] = true;
+                }
+            }
+        }
+    }
+
+    while covered.iter().any(|done| !done) {
+        let best = coverage
+            .iter()
+            .enumerate()
+            .filter(|(prime_index, _)| !chosen.contains(prime_index))
+            .max_by_key(|(prime_index, covers)| {
+                let gain = covers.iter().filter(|index| !covered[**index]).count();
+                (gain, primes[*prime_index].size())
+            })
+            .map(|(prime_index, _)| prime_index);
+
+        let Some(best) = best else {
+            break;
+        };
+        chosen.push(best);
+        for &index in &coverage[best] {
+            covered[index] = true;
+        }
+    }
+
+    chosen
+}
+
+struct CoverSearch<'a> {
+    // 每个片段可选的质蕴含项
+    candidates: Vec<Vec<usize>>,
+    coverage: &'a [Vec<usize>],
+    best: Vec<usize>,
+    nodes: usize,
+}
+
+impl CoverSearch<'_> {
+    // covered 记录每个片段被已选条目覆盖的次数；每次为候选最少的未覆盖片段分支
+    fn run(&mut self, chosen: &mut Vec<usize>, covered: &mut [usize]) {
+        self.nodes += 1;
+        if self.nodes > MAX_SEARCH_NODES || chosen.len() + 1 >= self.best.len() {
+            return;
+        }
+        let uncovered = (0..covered.len())
+            .filter(|element| covered[*element] == 0)
+            .min_by_key(|element| self.candidates[*element].len());
+        let Some(element) = uncovered else {
+            return;
+        };
+
+        for prime in self.candidates[element].clone() {
+            chosen.push(prime);
+            for &index in &self.coverage[prime] {
+                covered[index] += 1;
+            }
+            if covered.iter().all(|count| *count > 0) {
+                if chosen.len() < self.best.len() {
+                    self.best = chosen.clone();
+                }
+            } else {
+                self.run(chosen, covered);
+            }
+            for &index in &self.coverage[prime] {
+                covered[index] -= 1;
+            }
+            chosen.pop();
+        }
+    }
+}
+
+fn wildcard_for_prefix(prefix: u32) -> u32 {
+    if prefix == 0 {
+        u32::MAX
+    } else {
+        !(u32::MAX << (32 - prefix))
+    }
+}
+
+fn is_contiguous(wildcard: u32) -> bool {
+    wildcard.trailing_ones() + wildcard.leading_zeros() == 32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_odd_subnets_with_single_wildcard() {
+        let networks = (0..128)
+            .map(|index| format!("10.0.{}.0/24", index * 2 + 1))
+            .collect();
+        let result = compute_wildcard_match(networks).expect("should compute");
+
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].address, "10.0.1.0");
+        assert_eq!(result.entries[0].wildcard, "0.0.254.255");
+        assert!(!result.entries[0].contiguous);
+        assert_eq!(result.total_addresses, 128 * 256);
+    }
+
+    #[test]
+    fn keeps_exact_match_for_unmergeable_networks() {
+        let result = compute_wildcard_match(vec![
+            "192.168.1.0/24".into(),
+            "192.168.3.0/24".into(),
+            "192.168.4.0/24".into(),
+        ])
+        .expect("should compute");
+
+        let pairs: Vec<(String, String)> = result
+            .entries
+            .iter()
+            .map(|entry| (entry.address.clone(), entry.wildcard.clone()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("192.168.1.0".to_string(), "0.0.2.255".to_string()),
+                ("192.168.4.0".to_string(), "0.0.0.255".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_non_contiguous_wildcard() {
+        let result = expand_wildcard("10.0.0.0 0.0.254.255").expect("should expand");
+
+        assert!(!result.contiguous);
+        assert_eq!(result.total_networks, 128);
+        assert_eq!(result.networks[0], "10.0.0.0/24");
+        assert_eq!(result.networks[1], "10.0.2.0/24");
+        assert_eq!(result.total_addresses, 128 * 256);
+    }
+
+    #[test]
+    fn finds_smaller_cover_than_greedy() {
+        let hosts = [2, 3, 4, 6, 7, 11, 13, 15, 17, 19, 20, 21, 26, 27, 30, 31];
+        let inputs: Vec<Cube> = hosts
+            .iter()
+            .map(|host| Cube {
+                value: 0x0a00_0000 | host,
+                wildcard: 0,
+            })
+            .collect();
+        let primes = prime_implicants(&inputs);
+        let coverage: Vec<Vec<usize>> = primes
+            .iter()
+            .map(|prime| {
+                (0..inputs.len())
+                    .filter(|index| prime.contains(&inputs[*index]))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(greedy_cover(inputs.len(), &primes, &coverage).len(), 7);
+
+        let networks = hosts
+            .iter()
+            .map(|host| format!("10.0.0.{}", host))
+            .collect();
+        let result = compute_wildcard_match(networks).expect("should compute");
+        assert_eq!(result.entries.len(), 6);
+        assert!(result.minimal);
+
+        // 结果恰好匹配输入的主机
+        let mut matched: Vec<u32> = (0..32)
+            .filter(|host| {
+                result.entries.iter().any(|entry| {
+                    let value = u32::from(entry.address.parse::<Ipv4Addr>().unwrap());
+                    let wildcard = u32::from(entry.wildcard.parse::<Ipv4Addr>().unwrap());
+                    (0x0a00_0000 | host) & !wildcard == value
+                })
+            })
+            .collect();
+        matched.sort();
+        assert_eq!(matched, hosts);
+    }
+}
@@ -0,0 +1,422 @@
+use crate::ip_registry::{lookup_ipv6, SpecialPurposeInfo};
+use crate::ipv6_subnet::format_full_ipv6;
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MulticastInfo {
+    pub scope: String,
+    pub flags: Vec<String>,
+    pub solicited_node: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeredoInfo {
+    pub server: String,
+    pub client: String,
+    pub port: u16,
+    pub flags: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv6AddressInfo {
+    pub address: String,
+    pub full_address: String,
+    pub address_type: String,
+    pub multicast: Option<MulticastInfo>,
+    pub embedded_ipv4: Option<String>,
+    pub teredo: Option<TeredoInfo>,
+    pub eui64_mac: Option<String>,
+    pub ip6_arpa: String,
+    pub special_purpose: Option<SpecialPurposeInfo>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eui64Result {
+    pub prefix: String,
+    pub mac_address: String,
+    pub interface_id: String,
+    pub address: String,
+    pub full_address: String,
+    pub link_local_address: String,
+}
+
+#[tauri::command]
+pub fn inspect_ipv6_address(input: &str) -> Result<Ipv6AddressInfo, String> {
+    let normalized = input.trim();
+    let ip_part = normalized
+        .split_once('/')
+        .map(|(ip, _)| ip)
+        .unwrap_or(normalized);
+
+    let addr: Ipv6Addr = ip_part
+        .trim()
+        .parse()
+        .map_err(|_| "IPv6 地址格式不正确".to_string())?;
+
+    Ok(describe_ipv6(&addr))
+}
+
+#[tauri::command]
+pub fn generate_eui64_address(prefix: &str, mac: &str) -> Result<Eui64Result, String> {
+    let (ip_part, cidr_part) = prefix
+        .trim()
+        .split_once('/')
+        .ok_or_else(|| "请输入 /64 前缀，例如 2001:db8:1:2::/64".to_string())?;
+
+    let network: Ipv6Addr = ip_part
+        .trim()
+        .parse()
+        .map_err(|_| "IPv6 地址格式不正确".to_string())?;
+    let cidr: u32 = cidr_part
+        .trim()
+        .parse()
+        .map_err(|_| "CIDR 必须是 0 到 128 的整数".to_string())?;
+    if cidr != 64 {
+        return Err("EUI-64 地址只能基于 /64 前缀生成".to_string());
+    }
+
+    let mac_bytes = parse_mac(mac)?;
+    let interface_id = eui64_interface_id(&mac_bytes);
+    let network_value = u128::from(network) & (u128::MAX << 64);
+    let address = Ipv6Addr::from(network_value | interface_id as u128);
+    let link_local = Ipv6Addr::from((0xfe80u128 << 112) | interface_id as u128);
+
+    let id_segments = Ipv6Addr::from(interface_id as u128).segments();
+    Ok(Eui64Result {
+        prefix: format!("{}/64", Ipv6Addr::from(network_value)),
+        mac_address: format_mac(&mac_bytes),
+        interface_id: format!(
+            "{:x}:{:x}:{:x}:{:x}",
+            id_segments[4], id_segments[5], id_segments[6], id_segments[7]
+        ),
+        address: address.to_string(),
+        full_address: format_full_ipv6(&address),
+        link_local_address: link_local.to_string(),
+    })
+}
+
+#[tauri::command]
+pub fn extract_mac_from_ipv6(address: &str) -> Result<String, String> {
+    let normalized = address.trim();
+    let ip_part = normalized
+        .split_once('/')
+        .map(|(ip, _)| ip)
+        .unwrap_or(normalized);
+
+    let addr: Ipv6Addr = ip_part
+        .trim()
+        .parse()
+        .map_err(|_| "IPv6 地址格式不正确".to_string())?;
+
+    mac_from_interface_id(&addr)
+        .ok_or_else(|| "该地址的接口标识不是 EUI-64 格式（缺少 ff:fe）".to_string())
+}
+
+pub(crate) fn describe_ipv6(addr: &Ipv6Addr) -> Ipv6AddressInfo {
+    let value = u128::from(*addr);
+    let segments = addr.segments();
+    let kind = classify(value);
+
+    let multicast = matches!(kind, AddressKind::Multicast).then(|| MulticastInfo {
+        scope: multicast_scope(segments[0] & 0x000f),
+        flags: multicast_flags((segments[0] >> 4) & 0x000f),
+        solicited_node: in_prefix(value, 0xff02_0000_0000_0000_0000_0001_ff00_0000, 104),
+    });
+
+    let embedded_ipv4 = match kind {
+        AddressKind::Ipv4Mapped
+        | AddressKind::Ipv4Compatible
+        | AddressKind::Nat64
+        | AddressKind::Nat64Local => Some(Ipv4Addr::from(value as u32).to_string()),
+        AddressKind::SixToFour => Some(Ipv4Addr::from((value >> 80) as u32).to_string()),
+        AddressKind::Teredo => Some(Ipv4Addr::from(!(value as u32)).to_string()),
+        _ => None,
+    };
+
+    let teredo = matches!(kind, AddressKind::Teredo).then(|| TeredoInfo {
+        server: Ipv4Addr::from((value >> 64) as u32).to_string(),
+        client: Ipv4Addr::from(!(value as u32)).to_string(),
+        port: !segments[5],
+        flags: format!("0x{:04x}", segments[4]),
+    });
+
+    let eui64_mac = match kind {
+        AddressKind::Multicast | AddressKind::Teredo | AddressKind::Unspecified => None,
+        _ => mac_from_interface_id(addr),
+    };
+
+    Ipv6AddressInfo {
+        address: addr.to_string(),
+        full_address: format_full_ipv6(addr),
+        address_type: kind.label().to_string(),
+        multicast,
+        embedded_ipv4,
+        teredo,
+        eui64_mac,
+        ip6_arpa: build_ip6_arpa(addr),
+        special_purpose: lookup_ipv6(value),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum AddressKind {
+    Unspecified,
+    Loopback,
+    Ipv4Mapped,
+    Ipv4Compatible,
+    Nat64,
+    Nat64Local,
+    Teredo,
+    Documentation,
+    SixToFour,
+    UniqueLocal,
+    LinkLocal,
+    SiteLocal,
+    Multicast,
+    GlobalUnicast,
+    Reserved,
+}
+
+impl AddressKind {
+    fn label(self) -> &'static str {
+        match self {
+            AddressKind::Unspecified => "Unspecified",
+            AddressKind::Loopback => "Loopback",
+            AddressKind::Ipv4Mapped => "IPv4-mapped",
+            AddressKind::Ipv4Compatible => "IPv4-compatible (deprecated)",
+            AddressKind::Nat64 => "NAT64 well-known prefix",
+            AddressKind::Nat64Local => "NAT64 local-use prefix",
+            AddressKind::Teredo => "Teredo",
+            AddressKind::Documentation => "Documentation",
+            AddressKind::SixToFour => "6to4",
+            AddressKind::UniqueLocal => "Unique local (ULA)",
+            AddressKind::LinkLocal => "Link-local",
+            AddressKind::SiteLocal => "Site-local (deprecated)",
+            AddressKind::Multicast => "Multicast",
+            AddressKind::GlobalUnicast => "Global unicast",
+            AddressKind::Reserved => "Reserved",
+        }
+    }
+}
+
+fn classify(value: u128) -> AddressKind {
+    if value == 0 {
+        AddressKind::Unspecified
+    } else if value == 1 {
+        AddressKind::Loopback
+    } else if in_prefix(value, 0xffff_0000_0000, 96) {
+        AddressKind::Ipv4Mapped
+    } else if in_prefix(value, 0, 96) {
+        AddressKind::Ipv4Compatible
+    } else if in_prefix(value, 0x0064_ff9b << 96, 96) {
+        AddressKind::Nat64
+    } else if in_prefix(value, 0x0064_ff9b_0001 << 80, 48) {
+        AddressKind::Nat64Local
+    } else if in_prefix(value, 0x2001_0000 << 96, 32) {
+        AddressKind::Teredo
+    } else if in_prefix(value, 0x2001_0db8 << 96, 32) || in_prefix(value, 0x3fff << 112, 20) {
+        AddressKind::Documentation
+    } else if in_prefix(value, 0x2002 << 112, 16) {
+        AddressKind::SixToFour
+    } else if in_prefix(value, 0xfc00 << 112, 7) {
+        AddressKind::UniqueLocal
+    } else if in_prefix(value, 0xfe80 << 112, 10) {
+        AddressKind::LinkLocal
+    } else if in_prefix(value, 0xfec0 << 112, 10) {
+        AddressKind::SiteLocal
+    } else if in_prefix(value, 0xff00 << 112, 8) {
+        AddressKind::Multicast
+    } else if in_prefix(value, 0x2000 << 112, 3) {
+        AddressKind::GlobalUnicast
+    } else {
+        AddressKind::Reserved
+    }
+}
+
+fn in_prefix(value: u128, network: u128, prefix: u32) -> bool {
+    let mask = if prefix == 0 {
+        0
+    } else {
+        u128::MAX << (128 - prefix)
+    };
+    value & mask == network & mask
+}
+
+fn multicast_scope(scope: u16) -> String {
+    let name = match scope {
+        0x1 => "Interface-local",
+        0x2 => "Link-local",
+        0x3 => "Realm-local",
+        0x4 => "Admin-local",
+        0x5 => "Site-local",
+        0x8 => "Organization-local",
+        0xe => "Global",
+        0x0 | 0xf => "Reserved",
+        _ => "Unassigned",
+    };
+    format!("{} ({:x})", name, scope)
+}
+
+fn multicast_flags(flags: u16) -> Vec<String> {
+    let mut result = Vec::new();
+    if flags & 0x4 != 0 {
+        result.push("R (embedded RP)".to_string());
+    }
+    if flags & 0x2 != 0 {
+        result.push("P (prefix-based)".to_string());
+    }
+    if flags & 0x1 != 0 {
+        result.push("T (transient)".to_string());
+    }
+    result
+}
+
+// 接口标识中间为 ff:fe 时按修改型 EUI-64 还原 MAC（翻转 U/L 位）
+fn mac_from_interface_id(addr: &Ipv6Addr) -> Option<String> {
+    let octets = addr.octets();
+    if octets[11] != 0xff || octets[12] != 0xfe {
+        return None;
+    }
+
+    let mac = [
+        octets[8] ^ 0x02,
+        octets[9],
+        octets[10],
+        octets[13],
+        octets[14],
+        octets[15],
+    ];
+    Some(format_mac(&mac))
+}
+
+// 支持 00:1b:21:3c:4d:5e、00-1b-21-3c-4d-5e、001b.213c.4d5e（Cisco）、001b-213c-4d5e（H3C）及 001b213c4d5e
+fn parse_mac(input: &str) -> Result<[u8; 6], String> {
+    let trimmed = input.trim();
+    let groups: Vec<&str> = trimmed.split([':', '-', '.']).collect();
+    let valid_grouping = match groups.len() {
+        1 => groups[0].len() == 12,
+        3 => groups.iter().all(|group| group.len() == 4),
+        6 => groups.iter().all(|group| group.len() == 2),
+        _ => false,
+    };
+    if !valid_grouping {
+        return Err(format!("无法识别的 MAC 地址格式: {}", trimmed));
+    }
+
+    let hex: String = groups.concat();
+    if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(format!("MAC 地址包含非十六进制字符: {}", trimmed));
+    }
+
+    let mut mac = [0u8; 6];
+    for (index, byte) in mac.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16)
+            .map_err(|_| format!("MAC 地址包含非十六进制字符: {}", trimmed))?;
+    }
+    Ok(mac)
+}
+
+fn eui64_interface_id(mac: &[u8; 6]) -> u64 {
+    u64::from_be_bytes([
+        mac[0] ^ 0x02,
+        mac[1],
+        mac[2],
+        0xff,
+        0xfe,
+        mac[3],
+        mac[4],
+        mac[5],
+    ])
+}
+
+fn format_mac(mac: &[u8; 6]) -> String {
+    mac.iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn build_ip6_arpa(addr: &Ipv6Addr) -> String {
+    let hex = format!("{:032x}", u128::from(*addr));
+    let mut nibbles: Vec<String> = hex.chars().rev().map(|ch| ch.to_string()).collect();
+    nibbles.push("ip6.arpa".to_string());
+    nibbles.join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe(input: &str) -> Ipv6AddressInfo {
+        inspect_ipv6_address(input).expect("should parse")
+    }
+
+    #[test]
+    fn generates_eui64_from_vendor_mac_formats() {
+        for mac in [
+            "00:1B:21:3C:4D:5E",
+            "001b.213c.4d5e",
+            "001b-213c-4d5e",
+            "00-1b-21-3c-4d-5e",
+        ] {
+            let result = generate_eui64_address("2001:db8:1:2::/64", mac).expect("should generate");
+            assert_eq!(result.address, "2001:db8:1:2:21b:21ff:fe3c:4d5e");
+            assert_eq!(result.link_local_address, "fe80::21b:21ff:fe3c:4d5e");
+            assert_eq!(result.mac_address, "00:1b:21:3c:4d:5e");
+        }
+
+        assert!(generate_eui64_address("2001:db8::/48", "001b.213c.4d5e").is_err());
+        assert!(generate_eui64_address("2001:db8::/64", "001b.213c.4d5").is_err());
+        assert_eq!(
+            extract_mac_from_ipv6("2001:db8:1:2:21b:21ff:fe3c:4d5e").as_deref(),
+            Ok("00:1b:21:3c:4d:5e")
+        );
+    }
+
+    #[test]
+    fn decodes_embedded_ipv4() {
+        let mapped = describe("::ffff:192.0.2.1");
+        assert_eq!(mapped.address_type, "IPv4-mapped");
+        assert_eq!(mapped.embedded_ipv4.as_deref(), Some("192.0.2.1"));
+
+        let nat64 = describe("64:ff9b::c633:6401");
+        assert_eq!(nat64.address_type, "NAT64 well-known prefix");
+        assert_eq!(nat64.embedded_ipv4.as_deref(), Some("198.51.100.1"));
+
+        let six_to_four = describe("2002:c000:0204::1/48");
+        assert_eq!(six_to_four.address_type, "6to4");
+        assert_eq!(six_to_four.embedded_ipv4.as_deref(), Some("192.0.2.4"));
+    }
+
+    #[test]
+    fn decodes_teredo() {
+        let info = describe("2001:0:4136:e378:8000:63bf:3fff:fdd2");
+        let teredo = info.teredo.expect("should be teredo");
+        assert_eq!(teredo.server, "65.54.227.120");
+        assert_eq!(teredo.client, "192.0.2.45");
+        assert_eq!(teredo.port, 40000);
+        assert_eq!(teredo.flags, "0x8000");
+    }
+
+    #[test]
+    fn classifies_multicast_and_eui64() {
+        let info = describe("ff02::1:ff00:1234");
+        let multicast = info.multicast.expect("should be multicast");
+        assert_eq!(multicast.scope, "Link-local (2)");
+        assert!(multicast.solicited_node);
+        assert!(info.eui64_mac.is_none());
+
+        let link_local = describe("fe80::21b:21ff:fe3c:4d5e");
+        assert_eq!(link_local.address_type, "Link-local");
+        assert_eq!(link_local.eui64_mac.as_deref(), Some("00:1b:21:3c:4d:5e"));
+        assert!(link_local
+            .ip6_arpa
+            .starts_with("e.5.d.4.c.3.e.f.f.f.1.2.b.1.2.0"));
+        assert!(link_local.ip6_arpa.ends_with("0.8.e.f.ip6.arpa"));
+    }
+}
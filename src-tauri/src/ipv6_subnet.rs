@@ -1,6 +1,7 @@
+use crate::ipv6_address::{describe_ipv6, Ipv6AddressInfo};
 use num_bigint::BigUint;
-use num_traits::{One, Zero};
-use serde::Serialize;
+use num_traits::{One, ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
 use std::net::Ipv6Addr;
 
 #[derive(Serialize)]
@@ -13,8 +14,44 @@ pub struct Ipv6SubnetResult {
     pub network_address_full: String,
     pub ip_range: String,
     pub addresses: Vec<String>,
+    pub address_info: Ipv6AddressInfo,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv6SplitRequest {
+    pub prefix: String,
+    pub child_prefix: u32,
+    pub offset: Option<String>,
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub used_prefixes: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv6ChildPrefix {
+    pub index: String,
+    pub prefix: String,
+    pub first_address: String,
+    pub last_address: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv6SplitResult {
+    pub parent: String,
+    pub child_prefix: u32,
+    pub child_count: String,
+    pub offset: String,
+    pub children: Vec<Ipv6ChildPrefix>,
+    pub next_free: Option<Ipv6ChildPrefix>,
+    pub errors: Vec<String>,
+}
+
+const DEFAULT_SPLIT_LIMIT: u32 = 256;
+const MAX_SPLIT_LIMIT: u32 = 4096;
+
 #[tauri::command]
 pub fn compute_ipv6_subnet(input: &str) -> Result<Ipv6SubnetResult, String> {
     let normalized = input.trim();
@@ -84,9 +121,182 @@ pub fn compute_ipv6_subnet(input: &str) -> Result<Ipv6SubnetResult, String> {
             format_full_ipv6(&broadcast_addr)
         ),
         addresses,
+        address_info: describe_ipv6(&ipv6_addr),
     })
 }
 
+#[tauri::command]
+pub fn split_ipv6_prefix(request: Ipv6SplitRequest) -> Result<Ipv6SplitResult, String> {
+    let (parent_network, parent_prefix) = parse_ipv6_cidr(&request.prefix)?;
+    let child_prefix = request.child_prefix;
+
+    if child_prefix > 128 {
+        return Err("子网前缀必须在 0 到 128 之间".to_string());
+    }
+    if child_prefix < parent_prefix {
+        return Err(format!(
+            "子网前缀 /{} 不能小于父网段前缀 /{}",
+            child_prefix, parent_prefix
+        ));
+    }
+
+    let child_count = BigUint::one() << (child_prefix - parent_prefix);
+    let offset = match request.offset.as_deref().map(str::trim) {
+        Some(value) if !value.is_empty() => value
+            .parse::<BigUint>()
+            .map_err(|_| "偏移量必须是非负整数".to_string())?,
+        _ => BigUint::zero(),
+    };
+    if offset >= child_count {
+        return Err(format!(
+            "偏移量超出范围，共有 {} 个子网",
+            format_biguint(&child_count)
+        ));
+    }
+
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_SPLIT_LIMIT)
+        .clamp(1, MAX_SPLIT_LIMIT);
+    let remaining = &child_count - &offset;
+    let page_size = std::cmp::min(remaining, BigUint::from(limit))
+        .to_u32()
+        .unwrap_or(limit);
+
+    // offset 小于子网数量，而子网数量最多 2^128，此时 offset 必然落在 u128 范围内
+    let start_index = offset.to_u128().unwrap_or(u128::MAX);
+    let children = (0..page_size as u128)
+        .map(|step| build_child(parent_network, child_prefix, start_index + step))
+        .collect();
+
+    let mut errors = Vec::new();
+    let mut used_ranges = Vec::new();
+    for raw in &request.used_prefixes {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let parsed = if trimmed.contains('/') {
+            parse_ipv6_cidr(trimmed)
+        } else {
+            trimmed
+                .parse::<Ipv6Addr>()
+                .map(|addr| (u128::from(addr), 128))
+                .map_err(|_| "IPv6 地址格式不正确".to_string())
+        };
+        match parsed {
+            Ok((network, prefix)) => {
+                used_ranges.push((network, network | !mask_for_prefix(prefix)));
+            }
+            Err(err) => errors.push(format!("{} -> {}", trimmed, err)),
+        }
+    }
+
+    let next_free = find_next_free_child(parent_network, parent_prefix, child_prefix, used_ranges)
+        .map(|index| build_child(parent_network, child_prefix, index));
+
+    Ok(Ipv6SplitResult {
+        parent: format!("{}/{}", Ipv6Addr::from(parent_network), parent_prefix),
+        child_prefix,
+        child_count: format_biguint(&child_count),
+        offset: offset.to_string(),
+        children,
+        next_free,
+        errors,
+    })
+}
+
+fn parse_ipv6_cidr(input: &str) -> Result<(u128, u32), String> {
+    let (ip_part, cidr_part) = input
+        .trim()
+        .split_once('/')
+        .ok_or_else(|| "请输入合法的 IPv6/CIDR，例如 2001:db8::/48".to_string())?;
+
+    let ipv6_addr: Ipv6Addr = ip_part
+        .trim()
+        .parse()
+        .map_err(|_| "IPv6 地址格式不正确".to_string())?;
+
+    let cidr: u32 = cidr_part
+        .trim()
+        .parse()
+        .map_err(|_| "CIDR 必须是 0 到 128 的整数".to_string())?;
+
+    if cidr > 128 {
+        return Err("CIDR 必须在 0 到 128 之间".to_string());
+    }
+
+    Ok((u128::from(ipv6_addr) & mask_for_prefix(cidr), cidr))
+}
+
+fn build_child(parent_network: u128, child_prefix: u32, index: u128) -> Ipv6ChildPrefix {
+    let host_bits = 128 - child_prefix;
+    let offset = if host_bits == 128 {
+        0
+    } else {
+        index << host_bits
+    };
+    let network = parent_network | offset;
+    let last = network | !mask_for_prefix(child_prefix);
+
+    Ipv6ChildPrefix {
+        index: index.to_string(),
+        prefix: format!("{}/{}", Ipv6Addr::from(network), child_prefix),
+        first_address: Ipv6Addr::from(network).to_string(),
+        last_address: Ipv6Addr::from(last).to_string(),
+    }
+}
+
+// 返回父网段内第一个与已用前缀均不重叠的子网序号
+fn find_next_free_child(
+    parent_network: u128,
+    parent_prefix: u32,
+    child_prefix: u32,
+    mut used_ranges: Vec<(u128, u128)>,
+) -> Option<u128> {
+    let parent_last = parent_network | !mask_for_prefix(parent_prefix);
+    let child_span = !mask_for_prefix(child_prefix);
+    let child_mask = mask_for_prefix(child_prefix);
+
+    used_ranges.sort_by_key(|(start, _)| *start);
+
+    let mut candidate = parent_network;
+    for (start, end) in used_ranges {
+        if end < candidate {
+            continue;
+        }
+        if start > candidate + child_span {
+            break;
+        }
+        // 跳到已用范围之后的下一个子网边界
+        let next = end.checked_add(1)?;
+        candidate = next.checked_add(child_span)? & child_mask;
+        if candidate > parent_last {
+            return None;
+        }
+    }
+
+    if candidate > parent_last {
+        return None;
+    }
+
+    let host_bits = 128 - child_prefix;
+    let relative = candidate - parent_network;
+    Some(if host_bits == 128 {
+        0
+    } else {
+        relative >> host_bits
+    })
+}
+
+fn mask_for_prefix(prefix: u32) -> u128 {
+    if prefix == 0 {
+        0
+    } else {
+        u128::MAX << (128 - prefix)
+    }
+}
+
 fn ipv6_to_biguint(addr: &Ipv6Addr) -> BigUint {
     BigUint::from_bytes_be(&addr.octets())
 }
@@ -99,7 +309,7 @@ fn biguint_to_ipv6(value: &BigUint) -> Ipv6Addr {
     Ipv6Addr::from(bytes)
 }
 
-fn format_biguint(value: &BigUint) -> String {
+pub(crate) fn format_biguint(value: &BigUint) -> String {
     let mut result = String::new();
     let digits = value.to_str_radix(10);
     let chars: Vec<char> = digits.chars().collect();
@@ -115,7 +325,7 @@ fn format_biguint(value: &BigUint) -> String {
     result.chars().rev().collect()
 }
 
-fn format_full_ipv6(addr: &Ipv6Addr) -> String {
+pub(crate) fn format_full_ipv6(addr: &Ipv6Addr) -> String {
     let segments = addr.segments();
     format!(
         "{:04x}:{:04x}:{:04x}:{:04x}:{:04x}:{:04x}:{:04x}:{:04x}",
@@ -129,3 +339,34 @@ fn format_full_ipv6(addr: &Ipv6Addr) -> String {
         segments[7]
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_pages_children_and_finds_next_free() {
+        let result = split_ipv6_prefix(Ipv6SplitRequest {
+            prefix: "2001:db8:abcd::/48".into(),
+            child_prefix: 56,
+            offset: Some("2".into()),
+            limit: Some(2),
+            used_prefixes: vec![
+                "2001:db8:abcd::/56".into(),
+                "2001:db8:abcd:100::1".into(),
+                "2001:db8:abcd:300::/56".into(),
+            ],
+        })
+        .expect("should split");
+
+        assert_eq!(result.child_count, "256");
+        let prefixes: Vec<&str> = result.children.iter().map(|c| c.prefix.as_str()).collect();
+        assert_eq!(
+            prefixes,
+            ["2001:db8:abcd:200::/56", "2001:db8:abcd:300::/56"]
+        );
+        let next_free = result.next_free.expect("should have free child");
+        assert_eq!(next_free.prefix, "2001:db8:abcd:200::/56");
+        assert_eq!(next_free.index, "2");
+    }
+}
@@ -0,0 +1,321 @@
+use crate::ipv6_subnet::format_biguint;
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use serde::Serialize;
+use std::net::Ipv6Addr;
+
+#[derive(Clone, Copy)]
+struct Range {
+    start: u128,
+    end: u128,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv6AggregateResult {
+    pub normalized_inputs: Vec<String>,
+    pub precise_summary: Vec<String>,
+    pub approximate_summary: String,
+    pub non_precise_summary: Ipv6NonPreciseSummary,
+    pub errors: Vec<String>,
+}
+
+// IPv6 地址数量可能超过 u64 甚至 u128（::/0），统一以千分位字符串返回
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ipv6NonPreciseSummary {
+    pub cidr: String,
+    pub total_addresses: String,
+    pub input_addresses: String,
+    pub extra_addresses: String,
+    pub extra_percentage: f64,
+}
+
+#[tauri::command]
+pub fn aggregate_ipv6(items: Vec<String>) -> Result<Ipv6AggregateResult, String> {
+    let mut ranges: Vec<Range> = Vec::new();
+    let mut normalized_inputs: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+
+    for raw in items {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match parse_entry(trimmed) {
+            Ok((range, canonical)) => {
+                ranges.push(range);
+                normalized_inputs.push(canonical);
+            }
+            Err(err) => {
+                errors.push(format!("{} -> {}", trimmed, err));
+            }
+        }
+    }
+
+    if ranges.is_empty() {
+        return Err("没有可汇总的 IPv6 数据".into());
+    }
+
+    ranges.sort_by_key(|r| r.start);
+    let merged = merge_ranges(ranges);
+
+    let mut precise_summary: Vec<String> = Vec::new();
+    for range in &merged {
+        precise_summary.extend(range_to_cidrs(range.start, range.end));
+    }
+
+    let approximate_summary =
+        minimal_supernet(merged.first().unwrap().start, merged.last().unwrap().end);
+    let non_precise_summary = calculate_non_precise_summary(&merged)?;
+
+    Ok(Ipv6AggregateResult {
+        normalized_inputs,
+        precise_summary,
+        approximate_summary,
+        non_precise_summary,
+        errors,
+    })
+}
+
+fn parse_entry(entry: &str) -> Result<(Range, String), String> {
+    if entry.contains('/') {
+        parse_cidr(entry)
+    } else if entry.contains('-') {
+        parse_range(entry)
+    } else {
+        parse_single(entry)
+    }
+}
+
+fn parse_cidr(entry: &str) -> Result<(Range, String), String> {
+    let parts: Vec<&str> = entry.split('/').collect();
+    if parts.len() != 2 {
+        return Err("CIDR 格式不正确".into());
+    }
+    let ip: Ipv6Addr = parts[0]
+        .trim()
+        .parse()
+        .map_err(|_| "无效的 IPv6 地址".to_string())?;
+    let prefix: u32 = parts[1]
+        .trim()
+        .parse()
+        .map_err(|_| "CIDR 前缀必须是数字".to_string())?;
+    if prefix > 128 {
+        return Err("CIDR 前缀必须在 0-128 之间".into());
+    }
+
+    let start = u128::from(ip) & mask_for_prefix(prefix);
+    let end = start | !mask_for_prefix(prefix);
+
+    Ok((
+        Range { start, end },
+        format!("{}/{}", Ipv6Addr::from(start), prefix),
+    ))
+}
+
+fn parse_range(entry: &str) -> Result<(Range, String), String> {
+    let parts: Vec<&str> = entry.split('-').collect();
+    if parts.len() != 2 {
+        return Err("范围格式不正确".into());
+    }
+
+    let start_ip: Ipv6Addr = parts[0]
+        .trim()
+        .parse()
+        .map_err(|_| "无效的起始 IP".to_string())?;
+    let start_u128 = u128::from(start_ip);
+
+    let end_str = parts[1].trim();
+    let end_ip = if end_str.contains(':') {
+        end_str
+            .parse::<Ipv6Addr>()
+            .map_err(|_| "无效的结束 IP".to_string())?
+    } else {
+        // 简化写法只替换最后一段，例如 2001:db8::10-1f
+        let mut segments = start_ip.segments();
+        let value = u16::from_str_radix(end_str, 16)
+            .map_err(|_| "简化范围的结束值必须是十六进制数".to_string())?;
+        segments[7] = value;
+        Ipv6Addr::from(segments)
+    };
+
+    let end_u128 = u128::from(end_ip);
+    if end_u128 < start_u128 {
+        return Err("结束 IP 必须大于或等于起始 IP".into());
+    }
+
+    Ok((
+        Range {
+            start: start_u128,
+            end: end_u128,
+        },
+        format!("{} - {}", start_ip, end_ip),
+    ))
+}
+
+fn parse_single(entry: &str) -> Result<(Range, String), String> {
+    let ip: Ipv6Addr = entry
+        .trim()
+        .parse()
+        .map_err(|_| "无效的 IPv6 地址".to_string())?;
+    let value = u128::from(ip);
+    Ok((
+        Range {
+            start: value,
+            end: value,
+        },
+        ip.to_string(),
+    ))
+}
+
+fn merge_ranges(ranges: Vec<Range>) -> Vec<Range> {
+    if ranges.is_empty() {
+        return ranges;
+    }
+
+    let mut merged: Vec<Range> = Vec::new();
+    let mut current = ranges[0];
+
+    for range in ranges.into_iter().skip(1) {
+        if range.start <= current.end.saturating_add(1) {
+            current.end = current.end.max(range.end);
+        } else {
+            merged.push(current);
+            current = range;
+        }
+    }
+    merged.push(current);
+
+    merged
+}
+
+fn range_to_cidrs(start: u128, end: u128) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = start;
+
+    loop {
+        let max_trailing = current.trailing_zeros();
+        let mut prefix = 128 - max_trailing;
+
+        while current.saturating_add(host_span(prefix)) > end {
+            prefix += 1;
+        }
+
+        result.push(format!("{}/{}", Ipv6Addr::from(current), prefix));
+
+        match current
+            .checked_add(host_span(prefix))
+            .and_then(|last| last.checked_add(1))
+        {
+            Some(next) if next <= end => current = next,
+            _ => break,
+        }
+    }
+    result
+}
+
+// 前缀块内除首地址外的地址数量，即块大小减一，避免 /0 时溢出
+fn host_span(prefix: u32) -> u128 {
+    if prefix == 0 {
+        u128::MAX
+    } else {
+        (1u128 << (128 - prefix)) - 1
+    }
+}
+
+fn minimal_supernet(start: u128, end: u128) -> String {
+    let diff = start ^ end;
+    let prefix = diff.leading_zeros();
+    let network = start & mask_for_prefix(prefix);
+    format!("{}/{}", Ipv6Addr::from(network), prefix)
+}
+
+fn calculate_non_precise_summary(merged_ranges: &[Range]) -> Result<Ipv6NonPreciseSummary, String> {
+    let (Some(first), Some(last)) = (merged_ranges.first(), merged_ranges.last()) else {
+        return Err("合并后的范围不能为空".into());
+    };
+    let start = first.start;
+    let end = last.end;
+
+    // 计算最小超网
+    let diff = start ^ end;
+    let prefix = diff.leading_zeros();
+    let network = start & mask_for_prefix(prefix);
+
+    // CIDR 表示
+    let cidr = format!("{}/{}", Ipv6Addr::from(network), prefix);
+
+    // 计算总地址数
+    let total_addresses = BigUint::from(host_span(prefix)) + 1u32;
+
+    // 计算输入地址的实际数量
+    let mut input_addresses = BigUint::zero();
+    for range in merged_ranges {
+        input_addresses += BigUint::from(range.end - range.start) + 1u32;
+    }
+
+    // 计算额外地址数和百分比
+    let extra_addresses = if total_addresses > input_addresses {
+        &total_addresses - &input_addresses
+    } else {
+        BigUint::zero()
+    };
+    let extra_percentage = match (extra_addresses.to_f64(), total_addresses.to_f64()) {
+        (Some(extra), Some(total)) if total > 0.0 => (extra / total) * 100.0,
+        _ => 0.0,
+    };
+
+    Ok(Ipv6NonPreciseSummary {
+        cidr,
+        total_addresses: format_biguint(&total_addresses),
+        input_addresses: format_biguint(&input_addresses),
+        extra_addresses: format_biguint(&extra_addresses),
+        extra_percentage,
+    })
+}
+
+fn mask_for_prefix(prefix: u32) -> u128 {
+    if prefix == 0 {
+        0
+    } else {
+        u128::MAX << (128 - prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_merges_adjacent_prefixes() {
+        let result = aggregate_ipv6(vec![
+            "2001:db8::/33".into(),
+            "2001:db8:8000::/33".into(),
+            "2001:db8:1:2::1".into(),
+        ])
+        .expect("should aggregate");
+
+        assert_eq!(result.precise_summary, vec!["2001:db8::/32"]);
+        assert_eq!(result.approximate_summary, "2001:db8::/32");
+        assert_eq!(result.non_precise_summary.extra_addresses, "0");
+    }
+
+    #[test]
+    fn aggregate_handles_ranges_and_full_space() {
+        let result = aggregate_ipv6(vec!["2001:db8::1-7".into()]).expect("should aggregate");
+        assert_eq!(
+            result.precise_summary,
+            vec!["2001:db8::1/128", "2001:db8::2/127", "2001:db8::4/126"]
+        );
+
+        let result =
+            aggregate_ipv6(vec!["::/1".into(), "8000::/1".into()]).expect("should aggregate");
+        assert_eq!(result.precise_summary, vec!["::/0"]);
+        assert_eq!(
+            result.non_precise_summary.total_addresses,
+            "340,282,366,920,938,463,463,374,607,431,768,211,456"
+        );
+    }
+}
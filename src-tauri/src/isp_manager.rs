@@ -11,7 +11,7 @@ use zip::ZipArchive;
 
 const CONFIG_DIR_NAME: &str = "nat配置工具";
 const ELASTIC_IP_FILENAME: &str = "elastic_ip_config.yaml";
-const ISP_DATA_FILENAME: &str = "isp_data.yaml";
+pub(crate) const ISP_DATA_FILENAME: &str = "isp_data.yaml";
 const DEFAULT_SCAN_SIZE: u32 = 256;
 const GITHUB_ZIP_URL: &str =
     "https://github.com/gaoyifan/china-operator-ip/archive/refs/heads/ip-lists.zip";
@@ -22,14 +22,18 @@ struct ConfigPaths {
     isp_data_file: PathBuf,
 }
 
-fn resolve_paths() -> Result<ConfigPaths, String> {
+pub(crate) fn config_dir() -> Result<PathBuf, String> {
     let home_dir =
         dirs::home_dir().ok_or_else(|| "无法定位用户主目录，无法初始化配置目录".to_string())?;
 
     let config_dir = home_dir.join(".config").join(CONFIG_DIR_NAME);
     fs::create_dir_all(&config_dir)
         .map_err(|err| format!("创建配置目录失败: {config_dir:?}, 错误: {err}"))?;
+    Ok(config_dir)
+}
 
+fn resolve_paths() -> Result<ConfigPaths, String> {
+    let config_dir = config_dir()?;
     let elastic_ip_file = config_dir.join(ELASTIC_IP_FILENAME);
     let isp_data_file = config_dir.join(ISP_DATA_FILENAME);
 
@@ -39,7 +43,7 @@ fn resolve_paths() -> Result<ConfigPaths, String> {
     })
 }
 
-fn read_text_file(path: &Path) -> Result<Option<String>, String> {
+pub(crate) fn read_text_file(path: &Path) -> Result<Option<String>, String> {
     match fs::read_to_string(path) {
         Ok(content) => Ok(Some(content)),
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
@@ -47,7 +51,7 @@ fn read_text_file(path: &Path) -> Result<Option<String>, String> {
     }
 }
 
-fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+pub(crate) fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
     let dir = path
         .parent()
         .ok_or_else(|| format!("无法确定文件所在目录: {path:?}"))?;
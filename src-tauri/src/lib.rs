@@ -4,13 +4,27 @@ use tauri::{
     tray::TrayIconBuilder,
 };
 
+mod ip_database;
 mod ip_location;
+mod ip_location_batch;
+mod ip_location_provider;
+mod ip_registry;
 mod ipv4_subnet;
 mod ipv4_summary;
+mod ipv4_vlsm;
+mod ipv4_wildcard;
+mod ipv6_address;
 mod ipv6_subnet;
+mod ipv6_summary;
 mod isp_manager;
 mod nat_batch_generator;
+mod nat_conflict;
+mod nat_detector;
 mod nat_parser;
+mod nat_rollback;
+mod nat_translator;
+mod public_ip;
+mod subnet_batch;
 mod template_batch;
 mod vsr_batch;
 
@@ -45,13 +59,39 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             ipv4_subnet::get_public_ip,
+            public_ip::get_public_ip_report,
+            public_ip::get_public_ip_services,
+            public_ip::update_public_ip_services,
+            public_ip::reset_public_ip_services,
             ipv4_subnet::compute_subnet,
+            subnet_batch::compute_subnets_batch,
+            subnet_batch::export_subnet_batch,
             ipv6_subnet::compute_ipv6_subnet,
+            ipv6_subnet::split_ipv6_prefix,
+            ipv6_summary::aggregate_ipv6,
+            ipv6_address::inspect_ipv6_address,
+            ipv6_address::generate_eui64_address,
+            ipv6_address::extract_mac_from_ipv6,
             ipv4_summary::aggregate_ipv4,
+            ipv4_summary::exclude_ipv4,
+            ipv4_summary::compare_ipv4_lists,
+            ipv4_vlsm::plan_vlsm,
+            ipv4_wildcard::compute_wildcard_match,
+            ipv4_wildcard::expand_wildcard,
+            ip_registry::lookup_special_purpose,
             ip_location::lookup_ip_location,
             ip_location::get_database_info,
+            ip_location::open_location_database,
             ip_location::debug_ip_query,
+            ip_location_batch::lookup_ip_locations_batch,
+            ip_location_batch::export_ip_location_batch,
+            ip_location_provider::get_location_provider_config,
+            ip_location_provider::update_location_provider_config,
+            ip_location_provider::clear_location_cache,
             nat_parser::parse_nat_config,
+            nat_translator::translate_nat_config,
+            nat_conflict::check_nat_conflicts,
+            nat_rollback::generate_nat_rollback,
             nat_batch_generator::process_excel_data,
             nat_batch_generator::convert_excel_to_entries,
             nat_batch_generator::generate_nat_commands,
@@ -1,12 +1,17 @@
-use crate::ip_location::lookup_ip_location;
+use crate::ip_location::resolve_location;
+use crate::ip_location_provider::flush_location_cache;
 use crate::isp_manager::{
     find_isp_for_ip, load_elastic_ip_mapping_internal, load_isp_data_internal,
 };
+use crate::nat_parser::NATParser;
+use crate::nat_rollback::rollback_script;
+use crate::nat_translator::{NatTranslationIssue, TargetDevice};
 use calamine::{open_workbook_auto, Data, Range, Reader};
 use regex::Regex;
 use rust_xlsxwriter::{Format, FormatAlign, Workbook};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::net::Ipv4Addr;
 use std::sync::OnceLock;
 use strsim::normalized_levenshtein;
@@ -206,7 +211,7 @@ fn parse_ipv4(value: &str) -> Result<Ipv4Addr, String> {
         .map_err(|err| format!("无效的 IPv4 地址: {value}, 错误: {err}"))
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PortRange {
     pub start: u16,
@@ -223,7 +228,17 @@ impl PortRange {
     }
 }
 
-fn parse_port_range(value: &str) -> Result<PortRange, String> {
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_range() {
+            write!(f, "{}-{}", self.start, self.end)
+        } else {
+            write!(f, "{}", self.start)
+        }
+    }
+}
+
+pub(crate) fn parse_port_range(value: &str) -> Result<PortRange, String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
         return Err("端口号不能为空".to_string());
@@ -717,6 +732,9 @@ pub struct GenerateNatCommandsRequest {
 #[serde(rename_all = "camelCase")]
 pub struct GenerateNatCommandsResponse {
     pub commands: Vec<String>,
+    pub rollback_commands: Vec<String>,
+    // 无法生成回退命令的条目，含重新解析失败的命令
+    pub rollback_skipped: Vec<NatTranslationIssue>,
     pub missing_elastic_ips: Vec<String>,
 }
 
@@ -917,7 +935,7 @@ pub async fn generate_nat_commands(
                         .unwrap_or_default(),
                     Err(_) => String::new(),
                 },
-                IspSource::Online => match lookup_ip_location(public_ip.to_string()).await {
+                IspSource::Online => match resolve_location(public_ip.to_string(), None).await {
                     Ok(info) => prefix_from_label(&info.location.isp),
                     Err(err) => {
                         eprintln!("在线 ISP 查询失败: {}", err);
@@ -941,11 +959,37 @@ pub async fn generate_nat_commands(
         }
     }
 
+    if matches!(isp_source, IspSource::Online) {
+        if let Err(err) = flush_location_cache() {
+            eprintln!("写入 ISP 查询缓存失败: {}", err);
+        }
+    }
+
     let mut missing_list = missing_elastic.into_iter().collect::<Vec<_>>();
     missing_list.sort();
 
+    // 将生成的命令重新解析，得到对应的回退脚本
+    let (vendor, target) = match device_type {
+        DeviceType::Huawei => ("huawei", TargetDevice::Huawei),
+        DeviceType::H3c => ("h3c", TargetDevice::H3c),
+    };
+    let parsed = NATParser::parse_config(&commands.join("\n"), vendor);
+    let rollback = rollback_script(&parsed.success_entries, target);
+    let mut rollback_skipped = rollback.skipped;
+    rollback_skipped.extend(
+        parsed
+            .failed_entries
+            .into_iter()
+            .map(|line| NatTranslationIssue {
+                source: line,
+                reason: "生成的命令未能重新解析，需手工编写回退命令".to_string(),
+            }),
+    );
+
     Ok(GenerateNatCommandsResponse {
         commands,
+        rollback_commands: rollback.commands,
+        rollback_skipped,
         missing_elastic_ips: missing_list,
     })
 }
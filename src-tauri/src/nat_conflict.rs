@@ -0,0 +1,361 @@
+use crate::nat_detector::detect_vendor;
+use crate::nat_parser::{
+    format_port, DiagnosticSeverity, NatAddress, NatEntry, NatParseResult, NatProtocol,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NatConflictKind {
+    // 同一公网地址、协议、端口被映射多次
+    DuplicateBinding,
+    // 同一公网地址的端口段部分重叠
+    OverlappingPorts,
+    // 不区分协议/端口的整机映射覆盖了同地址的端口映射
+    ShadowedMapping,
+    // 同一内部地址端口通过多个公网地址发布
+    MultiplePublicAddresses,
+    NameCollision,
+    DescriptionCollision,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NatConflict {
+    pub kind: NatConflictKind,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    // success_entries 中的下标
+    pub entries: Vec<usize>,
+    pub commands: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NatConflictReport {
+    pub conflicts: Vec<NatConflict>,
+    pub error_count: usize,
+    pub warning_count: usize,
+}
+
+#[tauri::command]
+pub fn check_nat_conflicts(result: NatParseResult) -> NatConflictReport {
+    let entries = &result.success_entries;
+    let mut conflicts = Vec::new();
+
+    for i in 0..entries.len() {
+        for j in i + 1..entries.len() {
+            if let Some(conflict) = check_pair(entries, i, j) {
+                conflicts.push(conflict);
+            }
+        }
+    }
+    check_inside_exposure(entries, &mut conflicts);
+    check_labels(&result, &mut conflicts);
+
+    let error_count = conflicts
+        .iter()
+        .filter(|conflict| conflict.severity == DiagnosticSeverity::Error)
+        .count();
+    NatConflictReport {
+        warning_count: conflicts.len() - error_count,
+        error_count,
+        conflicts,
+    }
+}
+
+fn check_pair(entries: &[NatEntry], i: usize, j: usize) -> Option<NatConflict> {
+    let (a, b) = (&entries[i], &entries[j]);
+    // 公网侧是否冲突取决于 global 侧的 VPN 实例
+    if a.global_vpn_instance != b.global_vpn_instance
+        || !protocols_overlap(a.protocol, b.protocol)
+        || !globals_overlap(a, b)
+    {
+        return None;
+    }
+
+    let global = endpoint_text(a);
+    let (kind, severity, message) = match (port_span(a), port_span(b)) {
+        (Some(left), Some(right)) if left == right => (
+            NatConflictKind::DuplicateBinding,
+            DiagnosticSeverity::Error,
+            format!("公网 {} 重复映射", global),
+        ),
+        (Some(left), Some(right)) if left.0 <= right.1 && right.0 <= left.1 => (
+            NatConflictKind::OverlappingPorts,
+            DiagnosticSeverity::Error,
+            format!(
+                "公网地址 {} 的端口 {} 与 {} 重叠",
+                a.global_ip,
+                format_port(&a.global_port),
+                format_port(&b.global_port)
+            ),
+        ),
+        (Some(_), Some(_)) => return None,
+        (None, None) => (
+            NatConflictKind::DuplicateBinding,
+            DiagnosticSeverity::Error,
+            format!("公网地址 {} 重复做整机映射", a.global_ip),
+        ),
+        _ => (
+            NatConflictKind::ShadowedMapping,
+            DiagnosticSeverity::Warning,
+            format!("公网地址 {} 的整机映射会覆盖同地址的端口映射", a.global_ip),
+        ),
+    };
+    Some(conflict(kind, severity, message, entries, vec![i, j]))
+}
+
+// 同一内部地址、协议、端口对应多个公网地址；反向流量只能选其一
+fn check_inside_exposure(entries: &[NatEntry], conflicts: &mut Vec<NatConflict>) {
+    let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (idx, entry) in entries.iter().enumerate() {
+        let key = format!(
+            "{}|{}|{}|{}",
+            entry.vpn_instance.as_deref().unwrap_or_default(),
+            entry.inside_ip,
+            entry.protocol,
+            format_port(&entry.inside_port)
+        );
+        groups.entry(key).or_default().push(idx);
+    }
+
+    for indexes in groups.into_values() {
+        let globals: BTreeSet<String> = indexes
+            .iter()
+            .map(|idx| entries[*idx].global_ip.to_string())
+            .collect();
+        if globals.len() < 2 {
+            continue;
+        }
+        let entry = &entries[indexes[0]];
+        let message = format!(
+            "内部 {}:{} 通过 {} 个公网地址发布：{}",
+            entry.inside_ip,
+            format_port(&entry.inside_port),
+            globals.len(),
+            globals.into_iter().collect::<Vec<_>>().join("、")
+        );
+        conflicts.push(conflict(
+            NatConflictKind::MultiplePublicAddresses,
+            DiagnosticSeverity::Warning,
+            message,
+            entries,
+            indexes,
+        ));
+    }
+}
+
+// 华为 nat server 名称必须唯一，H3C 按接口区分；H3C 未命名条目（"-"）不参与比较。
+// ASA 的条目名称取自 object，object NAT 与 twice NAT 引用同一 object 属正常配置
+fn check_labels(result: &NatParseResult, conflicts: &mut Vec<NatConflict>) {
+    let entries = &result.success_entries;
+    let mut names: BTreeMap<(String, Option<&str>, &str), Vec<usize>> = BTreeMap::new();
+    let mut descriptions: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (idx, entry) in entries.iter().enumerate() {
+        let name = entry.name.trim();
+        let vendor = entry_vendor(result, entry);
+        if !name.is_empty() && name != "-" && vendor != "cisco-asa" {
+            let scope = entry.scope_interface.as_deref();
+            names.entry((vendor, scope, name)).or_default().push(idx);
+        }
+        if let Some(description) = entry.description.as_deref().map(str::trim) {
+            if !description.is_empty() {
+                descriptions.entry(description).or_default().push(idx);
+            }
+        }
+    }
+
+    for ((_, _, name), indexes) in names {
+        if indexes.len() > 1 {
+            conflicts.push(conflict(
+                NatConflictKind::NameCollision,
+                DiagnosticSeverity::Error,
+                format!("名称 {} 被 {} 条映射使用", name, indexes.len()),
+                entries,
+                indexes,
+            ));
+        }
+    }
+    for (description, indexes) in descriptions {
+        if indexes.len() > 1 {
+            conflicts.push(conflict(
+                NatConflictKind::DescriptionCollision,
+                DiagnosticSeverity::Warning,
+                format!("描述 {} 被 {} 条映射使用", description, indexes.len()),
+                entries,
+                indexes,
+            ));
+        }
+    }
+}
+
+// 混合粘贴时按条目原命令重新识别厂商
+fn entry_vendor(result: &NatParseResult, entry: &NatEntry) -> String {
+    if result.device_type == "mixed" {
+        detect_vendor(&entry.command).vendor
+    } else {
+        result.device_type.clone()
+    }
+}
+
+fn conflict(
+    kind: NatConflictKind,
+    severity: DiagnosticSeverity,
+    message: String,
+    entries: &[NatEntry],
+    indexes: Vec<usize>,
+) -> NatConflict {
+    NatConflict {
+        kind,
+        severity,
+        message,
+        commands: indexes
+            .iter()
+            .map(|idx| entries[*idx].command.clone())
+            .collect(),
+        entries: indexes,
+    }
+}
+
+fn protocols_overlap(left: NatProtocol, right: NatProtocol) -> bool {
+    left == NatProtocol::Any || right == NatProtocol::Any || left == right
+}
+
+// 不区分协议或未指定端口时视为占用整个地址
+fn port_span(entry: &NatEntry) -> Option<(u16, u16)> {
+    if entry.protocol == NatProtocol::Any {
+        return None;
+    }
+    entry.global_port.map(|port| (port.start, port.end))
+}
+
+fn endpoint_text(entry: &NatEntry) -> String {
+    format!(
+        "{} {}:{}",
+        entry.protocol,
+        entry.global_ip,
+        format_port(&entry.global_port)
+    )
+}
+
+fn globals_overlap(left: &NatEntry, right: &NatEntry) -> bool {
+    match (&left.global_ip, &right.global_ip) {
+        (NatAddress::Interface, NatAddress::Interface) => {
+            address_interface(left) == address_interface(right)
+        }
+        (NatAddress::Named(a), NatAddress::Named(b)) => a == b,
+        (a, b) => match (address_span(a), address_span(b)) {
+            (Some(a), Some(b)) => a.0 <= b.1 && b.0 <= a.1,
+            _ => false,
+        },
+    }
+}
+
+// current-interface 使用所在接口的地址
+fn address_interface(entry: &NatEntry) -> Option<&String> {
+    entry.interface.as_ref().or(entry.scope_interface.as_ref())
+}
+
+fn address_span(address: &NatAddress) -> Option<(IpAddr, IpAddr)> {
+    match address {
+        NatAddress::Ip(ip) => Some((*ip, *ip)),
+        NatAddress::Range(start, end) => Some((*start, *end)),
+        NatAddress::Network(IpAddr::V4(ip), prefix) if *prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(*prefix)).unwrap_or(0);
+            let start = u32::from(*ip) & mask;
+            Some((IpAddr::V4(start.into()), IpAddr::V4((start | !mask).into())))
+        }
+        NatAddress::Network(IpAddr::V6(ip), prefix) if *prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(*prefix)).unwrap_or(0);
+            let start = u128::from(*ip) & mask;
+            Some((IpAddr::V6(start.into()), IpAddr::V6((start | !mask).into())))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nat_parser::NATParser;
+
+    #[test]
+    fn reports_conflicts_between_mappings() {
+        let config = "nat server web protocol tcp global 203.0.113.1 80 inside 10.0.0.5 80\n\
+                      nat server web protocol tcp global 203.0.113.1 80 inside 10.0.0.6 80\n\
+                      nat server range protocol tcp global 203.0.113.1 8000 8100 inside 10.0.0.7 8000 8100\n\
+                      nat server alt protocol tcp global 203.0.113.1 8080 inside 10.0.0.8 8080\n\
+                      nat server whole global 203.0.113.2 inside 10.0.0.9\n\
+                      nat server ssh protocol tcp global 203.0.113.2 22 inside 10.0.0.10 22\n\
+                      nat server web2 protocol tcp global 203.0.113.3 80 inside 10.0.0.5 80";
+        let report = check_nat_conflicts(NATParser::parse_config(config, "huawei"));
+        let kinds: Vec<(NatConflictKind, Vec<usize>)> = report
+            .conflicts
+            .iter()
+            .map(|conflict| (conflict.kind, conflict.entries.clone()))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                (NatConflictKind::DuplicateBinding, vec![0, 1]),
+                (NatConflictKind::OverlappingPorts, vec![2, 3]),
+                (NatConflictKind::ShadowedMapping, vec![4, 5]),
+                (NatConflictKind::MultiplePublicAddresses, vec![0, 6]),
+                (NatConflictKind::NameCollision, vec![0, 1]),
+            ]
+        );
+        assert_eq!(report.error_count, 3);
+        assert_eq!(report.warning_count, 2);
+    }
+
+    #[test]
+    fn compares_global_side_vpn_instance() {
+        let config = "nat server protocol tcp global 203.0.113.1 80 inside 10.0.0.5 80 vpn-instance a\n\
+                      nat server protocol tcp global 203.0.113.1 80 inside 10.0.0.6 80 vpn-instance b\n\
+                      nat server protocol tcp global 203.0.113.2 80 vpn-instance x inside 10.0.0.7 80\n\
+                      nat server protocol tcp global 203.0.113.2 80 vpn-instance y inside 10.0.0.8 80";
+        let report = check_nat_conflicts(NATParser::parse_config(config, "h3c"));
+        let kinds: Vec<(NatConflictKind, Vec<usize>)> = report
+            .conflicts
+            .iter()
+            .map(|conflict| (conflict.kind, conflict.entries.clone()))
+            .collect();
+        assert_eq!(kinds, vec![(NatConflictKind::DuplicateBinding, vec![0, 1])]);
+    }
+
+    #[test]
+    fn scopes_names_by_vendor_and_interface() {
+        let asa = "object network WEB\n\
+                   host 10.0.0.5\n\
+                   nat (inside,outside) static 203.0.113.5\n\
+                   object network PUBLIC\n\
+                   host 203.0.113.6\n\
+                   nat (inside,outside) source static WEB PUBLIC";
+        let result = NATParser::parse_config(asa, "cisco-asa");
+        assert_eq!(result.success_entries.len(), 2);
+        assert_eq!(
+            result.success_entries[0].name,
+            result.success_entries[1].name
+        );
+        let report = check_nat_conflicts(result);
+        assert!(report
+            .conflicts
+            .iter()
+            .all(|conflict| conflict.kind != NatConflictKind::NameCollision));
+
+        let h3c = "interface GigabitEthernet1/0/1\n\
+                   nat server protocol tcp global 203.0.113.1 80 inside 10.0.0.5 80 rule 100\n\
+                   interface GigabitEthernet1/0/2\n\
+                   nat server protocol tcp global 203.0.113.2 80 inside 10.0.0.5 80 rule 100";
+        let report = check_nat_conflicts(NATParser::parse_config(h3c, "h3c"));
+        assert_eq!(report.conflicts.len(), 1);
+        assert!(report
+            .conflicts
+            .iter()
+            .all(|conflict| conflict.kind != NatConflictKind::NameCollision));
+    }
+}
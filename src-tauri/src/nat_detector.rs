@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+const VENDORS: [&str; 5] = ["huawei", "h3c", "cisco-asa", "cisco-ios", "fortigate"];
+
+// 整体置信度低于该值且逐行识别出多个厂商时，按混合粘贴分段解析
+const MIXED_THRESHOLD: f64 = 0.7;
+
+// (厂商, 行首关键字, 权重)；sysname、hostname 等共用关键字同时计入多个厂商
+const PREFIX_SIGNALS: &[(&str, &str, u32)] = &[
+    ("huawei", "!Software Version V", 5),
+    ("huawei", "nat-policy", 3),
+    ("huawei", "destination-nat ", 3),
+    ("huawei", "rule name ", 1),
+    ("huawei", "security-policy", 2),
+    ("huawei", "firewall zone ", 2),
+    ("huawei", "sysname ", 1),
+    ("h3c", "version 7.", 4),
+    ("h3c", "nat outbound", 3),
+    ("h3c", "nat static ", 3),
+    ("h3c", "security-zone name ", 2),
+    ("h3c", "sysname ", 1),
+    ("cisco-asa", "ASA Version", 5),
+    ("cisco-asa", "object network ", 3),
+    ("cisco-asa", "nat (", 3),
+    ("cisco-asa", "nameif ", 2),
+    ("cisco-asa", "hostname ", 1),
+    ("cisco-ios", "ip nat inside source ", 3),
+    ("cisco-ios", "ip nat outside source ", 3),
+    ("cisco-ios", "ip nat pool ", 3),
+    ("cisco-ios", "hostname ", 1),
+    ("fortigate", "#config-version=FG", 5),
+    ("fortigate", "config firewall vip", 5),
+    ("fortigate", "config firewall ippool", 4),
+    ("fortigate", "set extip ", 3),
+    ("fortigate", "set mappedip ", 3),
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorScore {
+    pub vendor: String,
+    pub score: u32,
+}
+
+// 行号从 1 开始，包含首尾
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorSegment {
+    pub vendor: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorDetection {
+    pub vendor: String,
+    pub confidence: f64,
+    pub scores: Vec<VendorScore>,
+    pub mixed: bool,
+    pub segments: Vec<VendorSegment>,
+}
+
+pub(crate) fn detect_vendor(text: &str) -> VendorDetection {
+    let mut totals = [0u32; VENDORS.len()];
+    let mut line_vendors = Vec::new();
+    for line in text.lines() {
+        let scores = score_line(line.trim());
+        for (total, score) in totals.iter_mut().zip(scores) {
+            *total += score;
+        }
+        line_vendors.push(dominant_vendor(&scores));
+    }
+
+    let total: u32 = totals.iter().sum();
+    let best = dominant_vendor(&totals);
+    let confidence = match best {
+        Some(idx) => (f64::from(totals[idx]) / f64::from(total) * 100.0).round() / 100.0,
+        None => 0.0,
+    };
+
+    let segments = build_segments(&line_vendors);
+    let mixed = confidence < MIXED_THRESHOLD && segments.len() > 1;
+
+    let mut scores: Vec<VendorScore> = VENDORS
+        .iter()
+        .zip(totals)
+        .filter(|(_, score)| *score > 0)
+        .map(|(vendor, score)| VendorScore {
+            vendor: vendor.to_string(),
+            score,
+        })
+        .collect();
+    scores.sort_by_key(|item| Reverse(item.score));
+
+    VendorDetection {
+        vendor: match (mixed, best) {
+            (true, _) => "mixed".to_string(),
+            (false, Some(idx)) => VENDORS[idx].to_string(),
+            (false, None) => "unknown".to_string(),
+        },
+        confidence,
+        scores,
+        mixed,
+        segments,
+    }
+}
+
+fn score_line(line: &str) -> [u32; VENDORS.len()] {
+    let mut scores = [0u32; VENDORS.len()];
+    let mut add = |vendor: &str, weight: u32| {
+        if let Some(idx) = VENDORS.iter().position(|name| *name == vendor) {
+            scores[idx] += weight;
+        }
+    };
+
+    for (vendor, prefix, weight) in PREFIX_SIGNALS {
+        if line.starts_with(prefix) {
+            add(vendor, *weight);
+        }
+    }
+    if line.contains("display current-configuration") {
+        add("huawei", 1);
+        add("h3c", 1);
+    }
+
+    // nat server 两家语法相近：华为可带名称、zone、no-reverse，H3C 常带 description/vrrp/counting
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.starts_with(&["nat", "server"]) {
+        match tokens.get(2) {
+            Some(&"zone") => add("huawei", 2),
+            Some(token)
+                if !matches!(
+                    *token,
+                    "protocol" | "global" | "inside" | "acl" | "vpn-instance"
+                ) =>
+            {
+                add("huawei", 3)
+            }
+            _ => {}
+        }
+        for token in &tokens[2..] {
+            match *token {
+                "no-reverse" => add("huawei", 2),
+                "unr-route" => add("huawei", 1),
+                "description" | "counting" | "reversible" | "current-interface" => add("h3c", 2),
+                "vrrp" | "rule" => add("h3c", 1),
+                _ => {}
+            }
+        }
+    }
+
+    scores
+}
+
+// 得分最高且唯一的厂商；并列或全为 0 时无法判断
+fn dominant_vendor(scores: &[u32]) -> Option<usize> {
+    let max = *scores.iter().max()?;
+    if max == 0 || scores.iter().filter(|score| **score == max).count() > 1 {
+        return None;
+    }
+    scores.iter().position(|score| *score == max)
+}
+
+// 无特征的行（块内子命令、空行等）归入当前段；开头无特征的行归入第一个可识别的段
+fn build_segments(line_vendors: &[Option<usize>]) -> Vec<VendorSegment> {
+    let mut segments: Vec<VendorSegment> = Vec::new();
+    for (idx, vendor) in line_vendors.iter().enumerate() {
+        let line_no = idx + 1;
+        match (vendor, segments.last_mut()) {
+            (Some(vendor), Some(last)) if last.vendor != VENDORS[*vendor] => {
+                segments.push(VendorSegment {
+                    vendor: VENDORS[*vendor].to_string(),
+                    start_line: line_no,
+                    end_line: line_no,
+                });
+            }
+            (Some(vendor), None) => segments.push(VendorSegment {
+                vendor: VENDORS[*vendor].to_string(),
+                start_line: 1,
+                end_line: line_no,
+            }),
+            (_, Some(last)) => last.end_line = line_no,
+            (None, None) => {}
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_single_and_mixed_vendors() {
+        let huawei = "sysname USG6000\nnat server web protocol tcp global 203.0.113.1 80 inside 10.0.0.1 80 no-reverse";
+        let detection = detect_vendor(huawei);
+        assert_eq!(detection.vendor, "huawei");
+        assert!(detection.confidence > 0.8);
+        assert!(!detection.mixed);
+
+        let mixed = "nat server web protocol tcp global 203.0.113.1 80 inside 10.0.0.1 80 no-reverse\n\
+                     config firewall vip\n    edit \"web\"\n        set extip 203.0.113.2\n        set mappedip \"10.0.0.2\"\n    next\nend";
+        let detection = detect_vendor(mixed);
+        assert!(detection.mixed);
+        assert_eq!(detection.segments.len(), 2);
+        assert_eq!(detection.segments[1].vendor, "fortigate");
+        assert_eq!(detection.segments[1].start_line, 2);
+        assert_eq!(detection.segments[1].end_line, 7);
+    }
+}
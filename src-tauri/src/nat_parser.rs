@@ -1,255 +1,684 @@
-use regex::Regex;
-use serde::Serialize;
-use std::sync::OnceLock;
+use crate::ipv4_subnet::{classify_mask, prefix_from_mask};
+use crate::nat_batch_generator::{parse_port_range, PortRange};
+use crate::nat_detector::{detect_vendor, VendorDetection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr};
+use std::str::FromStr;
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct NatEntry {
     pub name: String,
-    pub protocol: String,
-    pub global_ip: String,
-    pub global_port: String,
-    pub inside_ip: String,
-    pub inside_port: String,
-    pub vrrp: Option<String>,
+    pub protocol: NatProtocol,
+    pub global_ip: NatAddress,
+    #[serde(with = "port_text", default)]
+    pub global_port: Option<PortRange>,
+    pub inside_ip: NatAddress,
+    #[serde(with = "port_text", default)]
+    pub inside_port: Option<PortRange>,
+    pub vrrp: Option<u16>,
     pub rule: Option<String>,
     pub description: Option<String>,
+    // global interface X 中使用其地址的接口；global current-interface 时为 None
+    pub interface: Option<String>,
+    // 配置所在的接口视图（H3C）或映射生效的接口（ASA 映射侧接口、FortiGate extintf）
+    pub scope_interface: Option<String>,
+    pub zone: Option<String>,
+    // global 侧的 vpn-instance
+    #[serde(default)]
+    pub global_vpn_instance: Option<String>,
+    pub vpn_instance: Option<String>,
+    pub acl: Option<String>,
+    #[serde(default)]
+    pub no_reverse: bool,
+    #[serde(default)]
+    pub reversible: bool,
+    #[serde(default)]
+    pub counting: bool,
     pub command: String,
+    #[serde(default)]
+    pub diagnostics: Vec<ParseDiagnostic>,
 }
 
-#[derive(Serialize)]
+// 序列化为 "tcp"、"udp"、"any" 或协议号文本，与原先的字符串字段保持一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum NatProtocol {
+    #[default]
+    Any,
+    Tcp,
+    Udp,
+    Icmp,
+    Number(u8),
+}
+
+impl fmt::Display for NatProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NatProtocol::Any => write!(f, "any"),
+            NatProtocol::Tcp => write!(f, "tcp"),
+            NatProtocol::Udp => write!(f, "udp"),
+            NatProtocol::Icmp => write!(f, "icmp"),
+            NatProtocol::Number(number) => write!(f, "{}", number),
+        }
+    }
+}
+
+impl FromStr for NatProtocol {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "any" | "ip" => Ok(NatProtocol::Any),
+            "tcp" | "6" => Ok(NatProtocol::Tcp),
+            "udp" | "17" => Ok(NatProtocol::Udp),
+            "icmp" | "1" => Ok(NatProtocol::Icmp),
+            other => other
+                .parse::<u8>()
+                .map(NatProtocol::Number)
+                .map_err(|_| format!("无法识别的协议 {}", value)),
+        }
+    }
+}
+
+impl From<NatProtocol> for String {
+    fn from(protocol: NatProtocol) -> Self {
+        protocol.to_string()
+    }
+}
+
+impl TryFrom<String> for NatProtocol {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+// 同样以文本序列化："1.1.1.1"、"1.1.1.1-1.1.1.9"、"10.0.0.0/24"、"interface"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub enum NatAddress {
+    Ip(IpAddr),
+    Range(IpAddr, IpAddr),
+    Network(IpAddr, u8),
+    // 使用接口地址，接口名记录在 NatEntry::interface
+    Interface,
+    // 无法解析为地址的对象名、FQDN 等
+    Named(String),
+}
+
+impl Default for NatAddress {
+    fn default() -> Self {
+        NatAddress::Named(String::new())
+    }
+}
+
+impl fmt::Display for NatAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NatAddress::Ip(ip) => write!(f, "{}", ip),
+            NatAddress::Range(start, end) => write!(f, "{}-{}", start, end),
+            NatAddress::Network(ip, prefix) => write!(f, "{}/{}", ip, prefix),
+            NatAddress::Interface => write!(f, "interface"),
+            NatAddress::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl From<&str> for NatAddress {
+    fn from(value: &str) -> Self {
+        let value = value.trim();
+        if value == "interface" {
+            return NatAddress::Interface;
+        }
+        if let Ok(ip) = value.parse() {
+            return NatAddress::Ip(ip);
+        }
+        if let Some((ip, prefix)) = value.split_once('/') {
+            if let (Ok(ip), Ok(prefix)) = (ip.parse(), prefix.parse()) {
+                return NatAddress::Network(ip, prefix);
+            }
+        }
+        if let Some((start, end)) = value.split_once('-') {
+            if let (Ok(start), Ok(end)) = (start.parse(), end.parse()) {
+                return NatAddress::Range(start, end);
+            }
+        }
+        NatAddress::Named(value.to_string())
+    }
+}
+
+impl From<String> for NatAddress {
+    fn from(value: String) -> Self {
+        NatAddress::from(value.as_str())
+    }
+}
+
+impl From<NatAddress> for String {
+    fn from(address: NatAddress) -> Self {
+        address.to_string()
+    }
+}
+
+// 端口在界面上仍以 "80"、"80-90"、"any" 文本展示
+mod port_text {
+    use super::{format_port, parse_port_text, PortRange};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        port: &Option<PortRange>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_port(port))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<PortRange>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_port_text(&text).map_err(de::Error::custom)
+    }
+}
+
+pub(crate) fn format_port(port: &Option<PortRange>) -> String {
+    match port {
+        Some(range) => range.to_string(),
+        None => "any".to_string(),
+    }
+}
+
+fn parse_port_text(text: &str) -> Result<Option<PortRange>, String> {
+    match text.trim() {
+        "" | "any" => Ok(None),
+        value => parse_port_range(value).map(Some),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+// start/end 为命令行内的字符列（从 0 开始，左闭右开），便于前端高亮
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ParseDiagnostic {
+    fn new(
+        severity: DiagnosticSeverity,
+        message: impl Into<String>,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        ParseDiagnostic {
+            severity,
+            message: message.into(),
+            start,
+            end,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
+pub struct FailedLine {
+    pub line: String,
+    pub diagnostics: Vec<ParseDiagnostic>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
 pub struct NatParseResult {
     pub success_entries: Vec<NatEntry>,
     pub failed_entries: Vec<String>,
+    pub failed_details: Vec<FailedLine>,
     pub device_type: String,
+    pub address_groups: Vec<NatAddressGroup>,
+    pub policy_rules: Vec<NatPolicyRule>,
+    pub static_mappings: Vec<NatStaticMapping>,
+    pub detection: Option<VendorDetection>,
+}
+
+// 地址池：华为 nat address-group / destination-nat address-group
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NatAddressGroup {
+    pub name: String,
+    pub kind: String,
+    pub mode: Option<String>,
+    pub sections: Vec<String>,
+    pub description: Option<String>,
+    pub context: Vec<String>,
+}
+
+// 华为 nat-policy 下的一条 rule 或 H3C 接口下的 nat outbound，context 保留块头和全部原始行
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NatPolicyRule {
+    pub name: String,
+    pub action: String,
+    pub disabled: bool,
+    pub source_zones: Vec<String>,
+    pub destination_zones: Vec<String>,
+    pub source_addresses: Vec<String>,
+    pub destination_addresses: Vec<String>,
+    pub services: Vec<String>,
+    pub egress_interface: Option<String>,
+    pub acl: Option<String>,
+    pub address_group: Option<String>,
+    pub easy_ip: bool,
+    pub options: Vec<String>,
+    pub translated_address: Option<String>,
+    pub translated_port: Option<String>,
+    pub description: Option<String>,
+    pub context: Vec<String>,
+}
+
+// H3C nat static：全局配置的映射需在接口下 nat static enable 后生效，interfaces 记录这些接口
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NatStaticMapping {
+    pub direction: String,
+    pub local_address: String,
+    pub global_address: String,
+    pub acl: Option<String>,
+    pub interfaces: Vec<String>,
+    pub command: String,
+}
+
+struct ConfigLine<'a> {
+    text: &'a str,
+    indent: usize,
+}
+
+impl NatParseResult {
+    // 没有具体诊断时，整行记为一条错误
+    fn push_failed(&mut self, line: &str, mut diagnostics: Vec<ParseDiagnostic>) {
+        if diagnostics.is_empty() {
+            diagnostics.push(ParseDiagnostic::new(
+                DiagnosticSeverity::Error,
+                "无法解析该配置行",
+                0,
+                line.chars().count(),
+            ));
+        }
+        self.failed_entries.push(line.to_string());
+        self.failed_details.push(FailedLine {
+            line: line.to_string(),
+            diagnostics,
+        });
+    }
 }
 
 pub struct NATParser;
 
 impl NATParser {
     pub fn parse_config(text: &str, device_type: &str) -> NatParseResult {
-        let mut success_entries = Vec::new();
-        let mut failed_entries = Vec::new();
+        if device_type == "auto" {
+            return Self::parse_auto(text);
+        }
 
-        for line in text.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
+        let mut result = NatParseResult {
+            success_entries: Vec::new(),
+            failed_entries: Vec::new(),
+            failed_details: Vec::new(),
+            device_type: device_type.to_string(),
+            address_groups: Vec::new(),
+            policy_rules: Vec::new(),
+            static_mappings: Vec::new(),
+            detection: None,
+        };
+        let lines = collect_lines(text);
 
-            if line.starts_with("nat server") {
-                match device_type {
-                    "huawei" => {
-                        if let Some(parsed) = Self::parse_huawei_command(line) {
-                            success_entries.push(parsed);
-                        } else {
-                            failed_entries.push(line.to_string());
-                        }
-                    }
-                    "h3c" => {
-                        if let Some(parsed) = Self::parse_h3c_command(line) {
-                            success_entries.push(parsed);
-                        } else {
-                            failed_entries.push(line.to_string());
-                        }
-                    }
-                    _ => {
-                        failed_entries.push(line.to_string());
-                    }
+        match device_type {
+            "huawei" => Self::parse_huawei_config(&lines, &mut result),
+            "h3c" => Self::parse_h3c_config(&lines, &mut result),
+            "cisco-asa" => Self::parse_asa_config(&lines, &mut result),
+            "cisco-ios" => Self::parse_ios_config(&lines, &mut result),
+            "fortigate" => Self::parse_fortigate_config(&lines, &mut result),
+            _ => {
+                for line in lines
+                    .iter()
+                    .filter(|line| line.text.starts_with("nat server"))
+                {
+                    result.push_failed(line.text, Vec::new());
                 }
             }
         }
 
-        NatParseResult {
-            success_entries,
-            failed_entries,
-            device_type: device_type.to_string(),
+        result
+    }
+
+    // 单一厂商直接按识别结果解析；混合粘贴按分段分别解析后合并
+    fn parse_auto(text: &str) -> NatParseResult {
+        let detection = detect_vendor(text);
+        // 不带名称、zone 等特征的 nat server 无法区分华为与 H3C，按两家通用的语法解析，厂商仍记为 unknown
+        if detection.vendor == "unknown" {
+            let mut result = Self::parse_config(text, "h3c");
+            result.device_type = detection.vendor.clone();
+            result.detection = Some(detection);
+            return result;
+        }
+        if !detection.mixed {
+            let mut result = Self::parse_config(text, &detection.vendor);
+            result.detection = Some(detection);
+            return result;
+        }
+
+        let raw_lines: Vec<&str> = text.lines().collect();
+        let mut result = NatParseResult {
+            device_type: detection.vendor.clone(),
+            ..Default::default()
+        };
+        for segment in &detection.segments {
+            let segment_text = raw_lines[segment.start_line - 1..segment.end_line].join("\n");
+            let part = Self::parse_config(&segment_text, &segment.vendor);
+            result.success_entries.extend(part.success_entries);
+            result.failed_entries.extend(part.failed_entries);
+            result.failed_details.extend(part.failed_details);
+            result.address_groups.extend(part.address_groups);
+            result.policy_rules.extend(part.policy_rules);
+            result.static_mappings.extend(part.static_mappings);
         }
+        result.detection = Some(detection);
+        result
     }
 
-    fn parse_huawei_command(command: &str) -> Option<NatEntry> {
-        let (processed, quoted_names) = preprocess_command(command);
-        let tokens: Vec<&str> = processed.split_whitespace().collect();
-        if tokens.len() < 2 || tokens[0] != "nat" || tokens[1] != "server" {
-            return None;
+    fn parse_huawei_config(lines: &[ConfigLine], result: &mut NatParseResult) {
+        let mut idx = 0;
+        while idx < lines.len() {
+            let text = lines[idx].text;
+            if text.starts_with("nat server") {
+                match Self::parse_huawei_command(text) {
+                    Ok(parsed) => result.success_entries.push(parsed),
+                    Err(diagnostics) => result.push_failed(text, diagnostics),
+                }
+                idx += 1;
+            } else if text.starts_with("nat address-group ")
+                || text.starts_with("destination-nat address-group ")
+            {
+                let (children, next) = take_block(lines, idx, is_address_group_child);
+                match parse_address_group(text, children) {
+                    Some(group) => result.address_groups.push(group),
+                    None => result.push_failed(text, Vec::new()),
+                }
+                idx = next;
+            } else if text == "nat-policy" || text.starts_with("nat-policy interzone ") {
+                let (children, next) = take_block(lines, idx, is_nat_policy_child);
+                Self::parse_huawei_nat_policy(text, children, result);
+                idx = next;
+            } else {
+                idx += 1;
+            }
         }
+    }
 
-        let mut idx = 2;
-        let mut name: Option<String> = None;
+    // 新版以 rule name 划分规则；旧版 nat-policy interzone 以 policy <id> 划分，安全区域来自块头
+    fn parse_huawei_nat_policy(header: &str, children: &[ConfigLine], result: &mut NatParseResult) {
+        let header_tokens: Vec<&str> = header.split_whitespace().collect();
+        let interzone = match header_tokens.as_slice() {
+            ["nat-policy", "interzone", first, second, "inbound"] => Some((*second, *first)),
+            ["nat-policy", "interzone", first, second, ..] => Some((*first, *second)),
+            _ => None,
+        };
 
-        while idx < tokens.len() && !is_huawei_keyword(tokens[idx]) {
-            let token = restore_token(tokens[idx], &quoted_names);
-            if name.is_none() {
-                name = Some(token);
+        let mut current: Option<NatPolicyRule> = None;
+        for line in children {
+            let tokens: Vec<&str> = line.text.split_whitespace().collect();
+            let rule_name = match tokens.as_slice() {
+                ["rule", "name", ..] => Some(
+                    line.text["rule name".len()..]
+                        .trim()
+                        .trim_matches('"')
+                        .to_string(),
+                ),
+                ["policy", id] if interzone.is_some() && is_port_number(id) => {
+                    Some(format!("{} policy {}", &header["nat-policy ".len()..], id))
+                }
+                _ => None,
+            };
+
+            if let Some(name) = rule_name {
+                if let Some(rule) = current.take() {
+                    result.policy_rules.push(rule);
+                }
+                let mut rule = NatPolicyRule {
+                    name,
+                    context: vec![header.to_string(), line.text.to_string()],
+                    ..Default::default()
+                };
+                if let Some((source_zone, destination_zone)) = interzone {
+                    rule.source_zones.push(source_zone.to_string());
+                    rule.destination_zones.push(destination_zone.to_string());
+                }
+                current = Some(rule);
+                continue;
+            }
+
+            match current.as_mut() {
+                Some(rule) => {
+                    rule.context.push(line.text.to_string());
+                    apply_policy_line(rule, &tokens, line.text);
+                }
+                None => result.push_failed(line.text, Vec::new()),
             }
-            idx += 1;
         }
 
-        let mut protocol = "any".to_string();
-        let mut global_ip = String::new();
-        let mut global_port = "any".to_string();
-        let mut inside_ip = String::new();
-        let mut inside_port = "any".to_string();
+        if let Some(rule) = current {
+            result.policy_rules.push(rule);
+        }
+    }
 
-        while idx < tokens.len() {
-            match tokens[idx] {
-                "protocol" => {
-                    if idx + 1 < tokens.len() {
-                        protocol = restore_token(tokens[idx + 1], &quoted_names);
-                        idx += 2;
-                    } else {
-                        idx += 1;
-                    }
+    fn parse_h3c_config(lines: &[ConfigLine], result: &mut NatParseResult) {
+        let mut static_interfaces = Vec::new();
+        let mut idx = 0;
+        while idx < lines.len() {
+            let text = lines[idx].text;
+            if text.starts_with("nat address-group ") {
+                let (children, next) = take_block(lines, idx, is_address_group_child);
+                match parse_address_group(text, children) {
+                    Some(group) => result.address_groups.push(group),
+                    None => result.push_failed(text, Vec::new()),
                 }
-                "global" => {
-                    if idx + 1 < tokens.len() {
-                        global_ip = restore_token(tokens[idx + 1], &quoted_names);
-                        idx += 2;
-
-                        if idx < tokens.len() && !is_huawei_keyword(tokens[idx]) {
-                            let first_port = restore_token(tokens[idx], &quoted_names);
-                            idx += 1;
-
-                            let second_port = if idx < tokens.len()
-                                && !is_huawei_keyword(tokens[idx])
-                                && is_port_number(tokens[idx])
-                                && is_port_number(&first_port)
-                            {
-                                let port = restore_token(tokens[idx], &quoted_names);
-                                idx += 1;
-                                Some(port)
-                            } else {
-                                None
-                            };
-
-                            global_port = match second_port {
-                                Some(end) => format!("{}-{}", first_port, end),
-                                None => first_port,
-                            };
-                        }
+                idx = next;
+            } else if let Some(interface) = text.strip_prefix("interface ") {
+                let interface = interface.trim();
+                let (children, next) = take_block(lines, idx, is_interface_child);
+                for line in children {
+                    // V7 为 nat static enable，V5 为 nat outbound static
+                    if matches!(line.text, "nat static enable" | "nat outbound static") {
+                        static_interfaces.push(interface.to_string());
                     } else {
-                        idx += 1;
+                        Self::parse_h3c_statement(line.text, Some(interface), result);
                     }
                 }
-                "inside" => {
-                    if idx + 1 < tokens.len() {
-                        inside_ip = restore_token(tokens[idx + 1], &quoted_names);
-                        idx += 2;
-
-                        if idx < tokens.len() && !is_huawei_keyword(tokens[idx]) {
-                            let first_port = restore_token(tokens[idx], &quoted_names);
-                            idx += 1;
-
-                            let second_port = if idx < tokens.len()
-                                && !is_huawei_keyword(tokens[idx])
-                                && is_port_number(tokens[idx])
-                                && is_port_number(&first_port)
-                            {
-                                let port = restore_token(tokens[idx], &quoted_names);
-                                idx += 1;
-                                Some(port)
-                            } else {
-                                None
-                            };
-
-                            inside_port = match second_port {
-                                Some(end) => format!("{}-{}", first_port, end),
-                                None => first_port,
-                            };
-                        }
-                    } else {
-                        idx += 1;
-                    }
+                idx = next;
+            } else {
+                Self::parse_h3c_statement(text, None, result);
+                idx += 1;
+            }
+        }
+
+        for mapping in result
+            .static_mappings
+            .iter_mut()
+            .filter(|mapping| mapping.interfaces.is_empty())
+        {
+            mapping.interfaces = static_interfaces.clone();
+        }
+    }
+
+    fn parse_h3c_statement(text: &str, interface: Option<&str>, result: &mut NatParseResult) {
+        let parsed = if text.starts_with("nat server") {
+            match Self::parse_h3c_command(text) {
+                Ok(mut entry) => {
+                    entry.scope_interface = interface.map(|name| name.to_string());
+                    result.success_entries.push(entry);
                 }
-                "zone" | "acl" => {
-                    idx += 1;
-                    if idx < tokens.len() && !is_huawei_keyword(tokens[idx]) {
-                        idx += 1;
+                Err(diagnostics) => result.push_failed(text, diagnostics),
+            }
+            return;
+        } else if text.starts_with("nat outbound") {
+            parse_h3c_outbound(text, interface).map(|rule| result.policy_rules.push(rule))
+        } else if text.starts_with("nat static ") {
+            parse_h3c_static(text, interface).map(|mapping| result.static_mappings.push(mapping))
+        } else {
+            return;
+        };
+
+        if parsed.is_none() {
+            result.push_failed(text, Vec::new());
+        }
+    }
+
+    // 先收集 object network / object service，再解析 object NAT 与 twice NAT（可能引用后面定义的对象）
+    fn parse_asa_config(lines: &[ConfigLine], result: &mut NatParseResult) {
+        let mut objects = AsaObjects::default();
+        let mut object_nats = Vec::new();
+        let mut twice_nats = Vec::new();
+
+        let mut idx = 0;
+        while idx < lines.len() {
+            let text = lines[idx].text;
+            if let Some(name) = text.strip_prefix("object network ") {
+                let name = name.trim();
+                let (children, next) = take_block(lines, idx, is_asa_object_child);
+                for line in children {
+                    if line.text.starts_with("nat ") {
+                        object_nats.push((name, line.text));
+                    } else {
+                        objects.add_network_line(name, line.text);
                     }
                 }
-                "no-reverse" | "reversible" | "unr-route" => {
-                    idx += 1;
+                idx = next;
+            } else if let Some(name) = text.strip_prefix("object service ") {
+                let (children, next) = take_block(lines, idx, is_asa_object_child);
+                for line in children {
+                    objects.add_service_line(name.trim(), line.text);
                 }
-                _ => {
-                    idx += 1;
+                idx = next;
+            } else {
+                if text.starts_with("nat (") {
+                    twice_nats.push(text);
                 }
+                idx += 1;
             }
         }
 
-        if global_ip.is_empty() || inside_ip.is_empty() {
-            return None;
+        for (name, text) in object_nats {
+            if parse_asa_object_nat(name, text, &objects, result).is_none() {
+                result.push_failed(text, Vec::new());
+            }
         }
+        for text in twice_nats {
+            if parse_asa_twice_nat(text, &objects, result).is_none() {
+                result.push_failed(text, Vec::new());
+            }
+        }
+    }
 
-        let name = name.unwrap_or_else(|| {
-            let display_global_port = if global_port.is_empty() {
-                "any".to_string()
+    // 接口下的 ip nat inside/outside 只是标记，不计入结果
+    fn parse_ios_config(lines: &[ConfigLine], result: &mut NatParseResult) {
+        for line in lines {
+            let text = line.text;
+            let parsed = if text.starts_with("ip nat pool ") {
+                parse_ios_pool(text).map(|group| result.address_groups.push(group))
+            } else if text.starts_with("ip nat inside source static ") {
+                parse_ios_static(text).map(|entry| result.success_entries.push(entry))
+            } else if text.starts_with("ip nat inside source ") {
+                parse_ios_dynamic(text).map(|rule| result.policy_rules.push(rule))
+            } else if text.starts_with("ip nat outside source ") {
+                None
             } else {
-                global_port.clone()
-            };
-            let display_inside_port = if inside_port.is_empty() {
-                "any".to_string()
-            } else {
-                inside_port.clone()
+                continue;
             };
-            format!(
-                "{} {}:{} -> {}:{}",
-                protocol, global_ip, display_global_port, inside_ip, display_inside_port
-            )
-        });
 
-        Some(NatEntry {
-            name,
-            protocol,
-            global_ip,
-            global_port,
-            inside_ip,
-            inside_port,
-            vrrp: None,
-            rule: None,
-            description: None,
-            command: command.to_string(),
-        })
+            if parsed.is_none() {
+                result.push_failed(text, Vec::new());
+            }
+        }
     }
 
-    fn parse_h3c_command(command: &str) -> Option<NatEntry> {
-        let protocol =
-            capture_token(h3c_protocol_re(), command).unwrap_or_else(|| "any".to_string());
-        let global_ip = capture_token(h3c_global_re(), command)?;
-        let inside_ip = capture_token(h3c_inside_re(), command)?;
-        let rule = capture_token(h3c_rule_re(), command).unwrap_or_else(|| "-".to_string());
-        let vrrp = capture_token(h3c_vrrp_re(), command).unwrap_or_else(|| "-".to_string());
+    // config firewall vip / ippool 下每个 edit ... next 为一条记录，嵌套的 config ... end 原样保留在 context 中
+    fn parse_fortigate_config(lines: &[ConfigLine], result: &mut NatParseResult) {
+        let mut section: Option<FortiSection> = None;
+        let mut edit: Option<FortiEdit> = None;
+        let mut nested = 0;
 
-        let description = capture_token(h3c_description_re(), command)
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| "-".to_string());
+        for line in lines {
+            let text = line.text;
+            if nested > 0 || (edit.is_some() && text.starts_with("config ")) {
+                if text.starts_with("config ") {
+                    nested += 1;
+                } else if text == "end" {
+                    nested -= 1;
+                }
+                if let Some(current) = edit.as_mut() {
+                    current.context.push(text.to_string());
+                }
+                continue;
+            }
 
-        let mut global_port = "any".to_string();
-        let mut inside_port = "any".to_string();
+            if text.starts_with("config ") {
+                section = match text {
+                    "config firewall vip" => Some(FortiSection::Vip),
+                    "config firewall ippool" => Some(FortiSection::IpPool),
+                    _ => None,
+                };
+                continue;
+            }
+            let Some(kind) = section else {
+                continue;
+            };
 
-        if protocol.to_lowercase() != "any" {
-            if let Some(caps) = h3c_double_port_re().captures(command) {
-                global_port = format!("{}-{}", &caps[1], &caps[2]);
-                inside_port = format!("{}-{}", &caps[3], &caps[4]);
-            } else if let Some(caps) = h3c_single_port_re().captures(command) {
-                global_port = caps[1].to_string();
-                inside_port = caps[2].to_string();
+            if let Some(name) = text.strip_prefix("edit ") {
+                edit = Some(FortiEdit {
+                    name: name.trim().trim_matches('"').to_string(),
+                    settings: HashMap::new(),
+                    context: vec![text.to_string()],
+                });
+            } else if text == "next" || text == "end" {
+                if let Some(current) = edit.take() {
+                    finish_fortigate_edit(kind, current, result);
+                }
+                if text == "end" {
+                    section = None;
+                }
+            } else if let Some(current) = edit.as_mut() {
+                current.context.push(text.to_string());
+                if let Some(setting) = text.strip_prefix("set ") {
+                    let (key, value) = setting.split_once(' ').unwrap_or((setting, ""));
+                    let value: Vec<&str> = value
+                        .split_whitespace()
+                        .map(|part| part.trim_matches('"'))
+                        .collect();
+                    current.settings.insert(key.to_string(), value.join(" "));
+                }
             }
         }
 
-        Some(NatEntry {
-            name: rule.clone(),
-            protocol,
-            global_ip,
-            global_port,
-            inside_ip,
-            inside_port,
-            vrrp: Some(vrrp),
-            rule: Some(rule),
-            description: Some(description),
-            command: command.to_string(),
-        })
+        if let Some(current) = edit {
+            finish_fortigate_edit(section.unwrap_or(FortiSection::Vip), current, result);
+        }
+    }
+
+    fn parse_huawei_command(command: &str) -> Result<NatEntry, Vec<ParseDiagnostic>> {
+        parse_server_command(command, ServerDialect::Huawei)
+    }
+
+    fn parse_h3c_command(command: &str) -> Result<NatEntry, Vec<ParseDiagnostic>> {
+        parse_server_command(command, ServerDialect::H3c)
     }
 }
 
@@ -258,86 +687,1066 @@ pub fn parse_nat_config(text: String, device_type: String) -> NatParseResult {
     NATParser::parse_config(&text, &device_type)
 }
 
-fn preprocess_command(command: &str) -> (String, Vec<String>) {
-    let mut processed = command.to_string();
-    let mut quoted_values = Vec::new();
+fn collect_lines(text: &str) -> Vec<ConfigLine<'_>> {
+    text.lines()
+        .filter_map(|raw| {
+            let text = raw.trim();
+            if text.is_empty() {
+                return None;
+            }
+            let indent = raw.len() - raw.trim_start().len();
+            Some(ConfigLine { text, indent })
+        })
+        .collect()
+}
+
+// 块内容为缩进更深的行；粘贴时丢失缩进的情况下，再按该块特有的关键字判断
+fn take_block<'a>(
+    lines: &'a [ConfigLine<'a>],
+    start: usize,
+    is_child: fn(&str) -> bool,
+) -> (&'a [ConfigLine<'a>], usize) {
+    let header_indent = lines[start].indent;
+    let mut end = start + 1;
+    while end < lines.len() {
+        let line = &lines[end];
+        let terminator = line.text.starts_with('#') || matches!(line.text, "quit" | "return");
+        if terminator || !(line.indent > header_indent || is_child(line.text)) {
+            break;
+        }
+        end += 1;
+    }
+    (&lines[start + 1..end], end)
+}
 
-    for (index, caps) in quoted_value_re().captures_iter(command).enumerate() {
-        let quoted = caps[1].to_string();
-        quoted_values.push(quoted.clone());
-        let placeholder = format!("QUOTED_NAME_{}", index);
-        processed = processed.replace(&format!("\"{}\"", quoted), &placeholder);
+fn is_address_group_child(text: &str) -> bool {
+    [
+        "section ",
+        "mode ",
+        "route ",
+        "description ",
+        "smart-nopat",
+        "srcip-car",
+        "address ",
+        "port-range ",
+    ]
+    .iter()
+    .any(|keyword| text.starts_with(keyword))
+}
+
+fn is_nat_policy_child(text: &str) -> bool {
+    [
+        "rule ",
+        "source-zone ",
+        "destination-zone ",
+        "source-address ",
+        "destination-address ",
+        "service ",
+        "action ",
+        "description ",
+        "egress-interface ",
+        "nat-type ",
+        "time-range ",
+        "policy ",
+        "address-group ",
+        "easy-ip",
+        "disable",
+        "enable",
+    ]
+    .iter()
+    .any(|keyword| text.starts_with(keyword))
+}
+
+fn parse_address_group(header: &str, children: &[ConfigLine]) -> Option<NatAddressGroup> {
+    let tokens: Vec<&str> = header.split_whitespace().collect();
+    let kind = if tokens[0] == "destination-nat" {
+        "destination"
+    } else {
+        "source"
+    };
+    let name = tokens.get(2)?.trim_matches('"').to_string();
+
+    let mut group = NatAddressGroup {
+        name,
+        kind: kind.to_string(),
+        mode: None,
+        sections: Vec::new(),
+        description: None,
+        context: vec![header.to_string()],
+    };
+
+    // 旧版单行写法：nat address-group 1 202.1.1.1 202.1.1.5
+    if let Some(section) = address_section(&tokens[3..]) {
+        group.sections.push(section);
     }
 
-    (processed, quoted_values)
+    for line in children {
+        group.context.push(line.text.to_string());
+        let tokens: Vec<&str> = line.text.split_whitespace().collect();
+        match tokens[0] {
+            // H3C 为 address 1.1.1.1 1.1.1.10；华为 section 后可带编号：section 0 1.1.1.1 1.1.1.10
+            "section" | "address" => {
+                let args = match tokens.get(1) {
+                    Some(first) if first.parse::<Ipv4Addr>().is_err() => &tokens[2..],
+                    _ => &tokens[1..],
+                };
+                if let Some(section) = address_section(args) {
+                    group.sections.push(section);
+                }
+            }
+            "mode" => group.mode = Some(tokens[1..].join(" ")),
+            "description" => group.description = Some(tokens[1..].join(" ")),
+            _ => {}
+        }
+    }
+
+    Some(group)
+}
+
+fn is_interface_child(text: &str) -> bool {
+    [
+        "nat outbound",
+        "nat server ",
+        "nat static enable",
+        "nat hairpin",
+        "ip address ",
+        "description ",
+        "port link-mode",
+    ]
+    .iter()
+    .any(|keyword| text.starts_with(keyword))
 }
 
-fn restore_token(token: &str, quoted_values: &[String]) -> String {
-    if let Some(rest) = token.strip_prefix("QUOTED_NAME_") {
-        if let Ok(index) = rest.parse::<usize>() {
-            if let Some(value) = quoted_values.get(index) {
-                return value.clone();
+// nat outbound 3000 address-group 1 no-pat reversible；不带地址组时使用接口地址（Easy IP）
+fn parse_h3c_outbound(text: &str, interface: Option<&str>) -> Option<NatPolicyRule> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut rule = NatPolicyRule {
+        action: "source-nat".to_string(),
+        egress_interface: interface.map(|name| name.to_string()),
+        ..Default::default()
+    };
+    if let Some(interface) = interface {
+        rule.context.push(format!("interface {}", interface));
+    }
+    rule.context.push(text.to_string());
+
+    let mut rule_name = None;
+    let mut idx = 2;
+    while idx < tokens.len() {
+        let token = tokens[idx];
+        match token {
+            // V7 命名地址组：address-group name pool1
+            "address-group" if tokens.get(idx + 1) == Some(&"name") => {
+                rule.address_group = Some(tokens.get(idx + 2)?.to_string());
+                idx += 3;
+            }
+            "address-group" | "name" | "rule" | "vpn-instance" | "priority" => {
+                let value = tokens.get(idx + 1)?.to_string();
+                match token {
+                    "address-group" => rule.address_group = Some(value),
+                    "name" => rule.acl = Some(value),
+                    "rule" => rule_name = Some(value),
+                    _ => rule.options.push(format!("{} {}", token, value)),
+                }
+                idx += 2;
+            }
+            "description" => {
+                rule.description = Some(tokens[idx + 1..].join(" "));
+                break;
+            }
+            _ if is_port_number(token) && rule.acl.is_none() => {
+                rule.acl = Some(token.to_string());
+                idx += 1;
+            }
+            _ => {
+                rule.options.push(token.to_string());
+                idx += 1;
             }
         }
     }
-    token.to_string()
+
+    rule.easy_ip = rule.address_group.is_none();
+    rule.name = rule_name.unwrap_or_else(|| {
+        format!(
+            "{} outbound {}",
+            interface.unwrap_or("global"),
+            rule.acl.as_deref().unwrap_or("any")
+        )
+    });
+    Some(rule)
 }
 
-fn capture_token(regex: &Regex, command: &str) -> Option<String> {
-    regex
-        .captures(command)
-        .and_then(|caps| caps.get(1))
-        .map(|m| m.as_str().to_string())
+// nat static outbound 10.1.1.1 202.1.1.1 / nat static outbound net-to-net 10.1.1.1 10.1.1.255 global 202.1.1.0 24
+// inbound 方向先写外部地址；V5 的 nat static 10.1.1.1 202.1.1.1 视为 outbound
+fn parse_h3c_static(text: &str, interface: Option<&str>) -> Option<NatStaticMapping> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let (direction, args) = match tokens.get(2) {
+        Some(&"inbound") => ("inbound", &tokens[3..]),
+        Some(&"outbound") => ("outbound", &tokens[3..]),
+        _ => ("outbound", &tokens[2..]),
+    };
+
+    let (first, second) = if args.first() == Some(&"net-to-net") {
+        let global_pos = args.iter().position(|token| *token == "global")?;
+        let local = address_section(&args[1..global_pos])?;
+        let global = address_spec(&args[global_pos + 1..])?;
+        (local, global)
+    } else {
+        let mut addresses = args
+            .iter()
+            .take_while(|token| **token != "acl")
+            .filter(|token| token.parse::<Ipv4Addr>().is_ok());
+        (addresses.next()?.to_string(), addresses.next()?.to_string())
+    };
+
+    let (local_address, global_address) = match direction {
+        "inbound" => (second, first),
+        _ => (first, second),
+    };
+    let acl = tokens
+        .iter()
+        .position(|token| *token == "acl")
+        .and_then(|pos| tokens.get(pos + 1))
+        .map(|acl| acl.to_string());
+
+    Some(NatStaticMapping {
+        direction: direction.to_string(),
+        local_address,
+        global_address,
+        acl,
+        interfaces: interface
+            .map(|name| vec![name.to_string()])
+            .unwrap_or_default(),
+        command: text.to_string(),
+    })
 }
 
-fn quoted_value_re() -> &'static Regex {
-    static QUOTED_RE: OnceLock<Regex> = OnceLock::new();
-    QUOTED_RE.get_or_init(|| Regex::new(r#""([^"]+)""#).unwrap())
+#[derive(Default)]
+struct AsaObjects {
+    networks: HashMap<String, String>,
+    descriptions: HashMap<String, String>,
+    services: HashMap<String, (NatProtocol, Option<PortRange>)>,
 }
 
-fn h3c_protocol_re() -> &'static Regex {
-    static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| Regex::new(r"\bprotocol\s+(\S+)").unwrap())
+impl AsaObjects {
+    fn add_network_line(&mut self, name: &str, text: &str) {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let address = match tokens.as_slice() {
+            ["host", ip] => Some(ip.to_string()),
+            ["subnet", ip, mask] => address_spec(&[ip, mask]),
+            ["range", start, end] => Some(format!("{}-{}", start, end)),
+            ["fqdn", .., host] => Some(host.to_string()),
+            ["description", ..] => {
+                let description = text["description".len()..].trim().to_string();
+                self.descriptions.insert(name.to_string(), description);
+                None
+            }
+            _ => None,
+        };
+        if let Some(address) = address {
+            self.networks.insert(name.to_string(), address);
+        }
+    }
+
+    // service tcp destination eq www / service tcp source eq 8080
+    fn add_service_line(&mut self, name: &str, text: &str) {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        if tokens.first() != Some(&"service") || tokens.len() < 2 {
+            return;
+        }
+        let Ok(protocol) = tokens[1].parse() else {
+            return;
+        };
+        // 无法识别的端口不登记，引用该对象的 nat 行记为解析失败
+        let port = match tokens.iter().position(|token| *token == "eq") {
+            Some(pos) => match tokens.get(pos + 1).and_then(|port| cisco_port(port)) {
+                Some(port) => Some(port),
+                None => return,
+            },
+            None => None,
+        };
+        self.services.insert(name.to_string(), (protocol, port));
+    }
+
+    fn address(&self, name: &str) -> String {
+        self.networks
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+}
+
+// 丢失缩进时，带 source 关键字的 nat 行是全局的 twice NAT，不属于对象
+fn is_asa_object_child(text: &str) -> bool {
+    if text.starts_with("nat (") {
+        return !text.contains(" source ");
+    }
+    [
+        "host ",
+        "subnet ",
+        "range ",
+        "fqdn ",
+        "description ",
+        "service ",
+    ]
+    .iter()
+    .any(|keyword| text.starts_with(keyword))
 }
 
-fn h3c_global_re() -> &'static Regex {
-    static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| Regex::new(r"\bglobal\s+(\S+)").unwrap())
+// nat (inside,outside) 返回 (真实侧接口, 映射侧接口)
+fn asa_interfaces(token: &str) -> Option<(String, String)> {
+    let inner = token.strip_prefix('(')?.strip_suffix(')')?;
+    let (real, mapped) = inner.split_once(',')?;
+    Some((real.to_string(), mapped.to_string()))
 }
 
-fn h3c_inside_re() -> &'static Regex {
-    static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| Regex::new(r"\binside\s+(\S+)").unwrap())
+// object network 下：nat (inside,outside) static 203.0.113.10 service tcp 80 8080 / nat (inside,outside) dynamic interface
+fn parse_asa_object_nat(
+    name: &str,
+    text: &str,
+    objects: &AsaObjects,
+    result: &mut NatParseResult,
+) -> Option<()> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let (_, mapped_if) = asa_interfaces(tokens.get(1)?)?;
+    let mapped = *tokens.get(3)?;
+    let real = objects.networks.get(name)?.clone();
+
+    match tokens[2] {
+        "static" => {
+            let service = tokens.iter().position(|token| *token == "service");
+            let (protocol, inside_port, global_port) = match service {
+                Some(pos) => (
+                    tokens.get(pos + 1)?.parse().ok()?,
+                    Some(cisco_port(tokens.get(pos + 2)?)?),
+                    Some(cisco_port(tokens.get(pos + 3)?)?),
+                ),
+                None => (NatProtocol::Any, None, None),
+            };
+            result.success_entries.push(NatEntry {
+                name: name.to_string(),
+                protocol,
+                global_ip: NatAddress::from(objects.address(mapped)),
+                global_port,
+                inside_ip: NatAddress::from(real),
+                inside_port,
+                vrrp: None,
+                rule: None,
+                description: objects.descriptions.get(name).cloned(),
+                scope_interface: Some(mapped_if),
+                reversible: true,
+                command: text.to_string(),
+                ..Default::default()
+            });
+        }
+        "dynamic" => result.policy_rules.push(NatPolicyRule {
+            name: name.to_string(),
+            action: "source-nat".to_string(),
+            source_addresses: vec![real],
+            address_group: (mapped != "interface").then(|| mapped.to_string()),
+            easy_ip: tokens[3..].contains(&"interface"),
+            egress_interface: Some(mapped_if),
+            description: objects.descriptions.get(name).cloned(),
+            context: vec![format!("object network {}", name), text.to_string()],
+            ..Default::default()
+        }),
+        _ => return None,
+    }
+    Some(())
 }
 
-fn h3c_rule_re() -> &'static Regex {
-    static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| Regex::new(r"\brule\s+(\S+)").unwrap())
+// nat (inside,outside) [after-auto] [1] source static REAL MAPPED [destination static A B] [service S1 S2] [description ...]
+fn parse_asa_twice_nat(
+    text: &str,
+    objects: &AsaObjects,
+    result: &mut NatParseResult,
+) -> Option<()> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let (_, mapped_if) = asa_interfaces(tokens.get(1)?)?;
+    let source = tokens.iter().position(|token| *token == "source")?;
+    let real = *tokens.get(source + 2)?;
+    let mapped = *tokens.get(source + 3)?;
+    let description = tokens
+        .iter()
+        .position(|token| *token == "description")
+        .map(|pos| tokens[pos + 1..].join(" "));
+    let destination = tokens
+        .iter()
+        .position(|token| *token == "destination")
+        .and_then(|pos| tokens.get(pos + 2))
+        .map(|name| objects.address(name));
+
+    match *tokens.get(source + 1)? {
+        "static" => {
+            let services = tokens
+                .iter()
+                .position(|token| *token == "service")
+                .and_then(|pos| Some((tokens.get(pos + 1)?, tokens.get(pos + 2)?)));
+            let (protocol, inside_port, global_port) = match services {
+                Some((real_service, mapped_service)) => {
+                    let (protocol, inside_port) = *objects.services.get(*real_service)?;
+                    let (_, global_port) = *objects.services.get(*mapped_service)?;
+                    (protocol, inside_port, global_port)
+                }
+                None => (NatProtocol::Any, None, None),
+            };
+            result.success_entries.push(NatEntry {
+                name: real.to_string(),
+                protocol,
+                global_ip: NatAddress::from(objects.address(mapped)),
+                global_port,
+                inside_ip: NatAddress::from(objects.address(real)),
+                inside_port,
+                vrrp: None,
+                rule: None,
+                description,
+                scope_interface: Some(mapped_if),
+                reversible: true,
+                command: text.to_string(),
+                ..Default::default()
+            });
+        }
+        "dynamic" => result.policy_rules.push(NatPolicyRule {
+            name: format!("{} -> {}", real, mapped),
+            action: "source-nat".to_string(),
+            source_addresses: vec![objects.address(real)],
+            destination_addresses: destination.into_iter().collect(),
+            address_group: (mapped != "interface").then(|| mapped.to_string()),
+            easy_ip: mapped == "interface",
+            egress_interface: Some(mapped_if),
+            description,
+            context: vec![text.to_string()],
+            ..Default::default()
+        }),
+        _ => return None,
+    }
+    Some(())
 }
 
-fn h3c_vrrp_re() -> &'static Regex {
-    static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| Regex::new(r"\bvrrp\s+(\d+)").unwrap())
+// Cisco 配置中常见的端口别名
+fn cisco_port(port: &str) -> Option<PortRange> {
+    port_value(port).map(|port| PortRange {
+        start: port,
+        end: port,
+    })
 }
 
-fn h3c_description_re() -> &'static Regex {
-    static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| Regex::new(r"description\s+(.+?)(?:\s+counting|\s+reversible|$)").unwrap())
+// ip nat pool POOL 203.0.113.10 203.0.113.20 netmask 255.255.255.0 | prefix-length 24
+fn parse_ios_pool(text: &str) -> Option<NatAddressGroup> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    Some(NatAddressGroup {
+        name: tokens.get(3)?.to_string(),
+        kind: "source".to_string(),
+        mode: None,
+        sections: vec![address_section(tokens.get(4..6)?)?],
+        description: None,
+        context: vec![text.to_string()],
+    })
 }
 
-fn h3c_double_port_re() -> &'static Regex {
-    static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| {
-        Regex::new(r"global\s+\S+\s+(\d+)\s+(\d+)\s+inside\s+\S+\s+(\d+)\s+(\d+)").unwrap()
+// ip nat inside source static [tcp|udp] LOCAL [LPORT] GLOBAL|interface IF [GPORT] [extendable]
+// ip nat inside source static network 10.1.1.0 203.0.113.0 /24
+fn parse_ios_static(text: &str) -> Option<NatEntry> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let args = &tokens[5..];
+    let (protocol, args) = match args.first() {
+        Some(&"tcp") => (NatProtocol::Tcp, &args[1..]),
+        Some(&"udp") => (NatProtocol::Udp, &args[1..]),
+        _ => (NatProtocol::Any, args),
+    };
+
+    let mut interface = None;
+    let (inside_ip, inside_port, global_ip, global_port) = if args.first() == Some(&"network") {
+        let mask = args.get(3)?;
+        let prefix = match mask.strip_prefix('/') {
+            Some(prefix) => prefix.parse().ok()?,
+            None => prefix_from_mask(u32::from(mask.parse::<Ipv4Addr>().ok()?))? as u8,
+        };
+        let local = NatAddress::Network(args.get(1)?.parse().ok()?, prefix);
+        let global = NatAddress::Network(args.get(2)?.parse().ok()?, prefix);
+        (local, None, global, None)
+    } else {
+        let with_ports = protocol != NatProtocol::Any;
+        let mut rest = args.iter().copied();
+        let local = NatAddress::Ip(rest.next()?.parse().ok()?);
+        let local_port = if with_ports {
+            Some(cisco_port(rest.next()?)?)
+        } else {
+            None
+        };
+        let global = match rest.next()? {
+            "interface" => {
+                interface = Some(rest.next()?.to_string());
+                NatAddress::Interface
+            }
+            ip => NatAddress::Ip(ip.parse().ok()?),
+        };
+        let global_port = if with_ports {
+            Some(cisco_port(rest.next()?)?)
+        } else {
+            None
+        };
+        (local, local_port, global, global_port)
+    };
+
+    Some(NatEntry {
+        name: format!(
+            "{} {}:{} -> {}:{}",
+            protocol,
+            global_ip,
+            format_port(&global_port),
+            inside_ip,
+            format_port(&inside_port)
+        ),
+        protocol,
+        global_ip,
+        global_port,
+        inside_ip,
+        inside_port,
+        vrrp: None,
+        rule: None,
+        description: None,
+        interface,
+        reversible: true,
+        command: text.to_string(),
+        ..Default::default()
     })
 }
 
-fn h3c_single_port_re() -> &'static Regex {
-    static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| Regex::new(r"global\s+\S+\s+(\d+)\s+inside\s+\S+\s+(\d+)").unwrap())
+// ip nat inside source list 1 pool POOL overload / ip nat inside source route-map RM interface Gi0/0 overload
+fn parse_ios_dynamic(text: &str) -> Option<NatPolicyRule> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut rule = NatPolicyRule {
+        action: "source-nat".to_string(),
+        context: vec![text.to_string()],
+        ..Default::default()
+    };
+
+    let mut idx = 4;
+    while idx < tokens.len() {
+        match tokens[idx] {
+            key @ ("list" | "route-map" | "pool" | "interface" | "vrf") => {
+                let value = tokens.get(idx + 1)?.to_string();
+                match key {
+                    "list" => rule.acl = Some(value),
+                    "pool" => rule.address_group = Some(value),
+                    "interface" => {
+                        rule.egress_interface = Some(value);
+                        rule.easy_ip = true;
+                    }
+                    _ => rule.options.push(format!("{} {}", key, value)),
+                }
+                idx += 2;
+            }
+            option => {
+                rule.options.push(option.to_string());
+                idx += 1;
+            }
+        }
+    }
+
+    if rule.address_group.is_none() && !rule.easy_ip {
+        return None;
+    }
+    rule.name = format!("inside source {}", tokens[4..6.min(tokens.len())].join(" "));
+    Some(rule)
+}
+
+#[derive(Clone, Copy)]
+enum FortiSection {
+    Vip,
+    IpPool,
+}
+
+struct FortiEdit {
+    name: String,
+    settings: HashMap<String, String>,
+    context: Vec<String>,
+}
+
+fn finish_fortigate_edit(section: FortiSection, edit: FortiEdit, result: &mut NatParseResult) {
+    let setting = |key: &str| edit.settings.get(key).cloned();
+    match section {
+        FortiSection::Vip => {
+            // 仅 static-nat 类型（默认）可对应到 nat server，负载均衡和 FQDN 类型记为失败
+            let static_nat =
+                !matches!(setting("type").as_deref(), Some(kind) if kind != "static-nat");
+            let (Some(global_ip), Some(inside_ip), true) =
+                (setting("extip"), setting("mappedip"), static_nat)
+            else {
+                result.push_failed(&edit.context.join("\n"), Vec::new());
+                return;
+            };
+            let ports = if setting("portforward").as_deref() == Some("enable") {
+                let protocol = setting("protocol").unwrap_or_else(|| "tcp".to_string());
+                let global_port = parse_port_text(&setting("extport").unwrap_or_default());
+                let inside_port = match setting("mappedport") {
+                    Some(port) => parse_port_text(&port),
+                    None => global_port.clone(),
+                };
+                match (protocol.parse(), global_port, inside_port) {
+                    (Ok(protocol), Ok(global_port), Ok(inside_port)) => {
+                        Some((protocol, global_port, inside_port))
+                    }
+                    _ => None,
+                }
+            } else {
+                Some((NatProtocol::Any, None, None))
+            };
+            let Some((protocol, global_port, inside_port)) = ports else {
+                result.push_failed(&edit.context.join("\n"), Vec::new());
+                return;
+            };
+
+            result.success_entries.push(NatEntry {
+                name: edit.name.clone(),
+                protocol,
+                global_ip: NatAddress::from(global_ip),
+                global_port,
+                inside_ip: NatAddress::from(inside_ip),
+                inside_port,
+                vrrp: None,
+                rule: None,
+                description: setting("comment"),
+                scope_interface: setting("extintf").filter(|name| name != "any"),
+                command: edit.context.join("\n"),
+                ..Default::default()
+            });
+        }
+        FortiSection::IpPool => result.address_groups.push(NatAddressGroup {
+            name: edit.name.clone(),
+            kind: "source".to_string(),
+            mode: setting("type"),
+            sections: match (setting("startip"), setting("endip")) {
+                (Some(start), Some(end)) if start != end => vec![format!("{}-{}", start, end)],
+                (Some(start), _) => vec![start],
+                _ => Vec::new(),
+            },
+            description: setting("comments"),
+            context: edit.context.clone(),
+        }),
+    }
+}
+
+fn address_section(args: &[&str]) -> Option<String> {
+    let addresses: Vec<&str> = args
+        .iter()
+        .copied()
+        .filter(|token| token.parse::<Ipv4Addr>().is_ok())
+        .take(2)
+        .collect();
+    match addresses.as_slice() {
+        [start, end] if start != end => Some(format!("{}-{}", start, end)),
+        [start, ..] => Some(start.to_string()),
+        [] => None,
+    }
+}
+
+fn apply_policy_line(rule: &mut NatPolicyRule, tokens: &[&str], text: &str) {
+    let rest = || tokens[1..].join(" ");
+    match tokens[0] {
+        "source-zone" => rule
+            .source_zones
+            .extend(tokens[1..].iter().map(|zone| zone.to_string())),
+        "destination-zone" => rule
+            .destination_zones
+            .extend(tokens[1..].iter().map(|zone| zone.to_string())),
+        "source-address" => rule.source_addresses.extend(address_spec(&tokens[1..])),
+        "destination-address" => rule
+            .destination_addresses
+            .extend(address_spec(&tokens[1..])),
+        "service" => rule.services.push(rest()),
+        "egress-interface" => rule.egress_interface = tokens.get(1).map(|name| name.to_string()),
+        "description" => rule.description = Some(text["description".len()..].trim().to_string()),
+        "disable" => rule.disabled = true,
+        "nat-type" if rule.action.is_empty() => rule.action = rest(),
+        "action" => apply_policy_action(rule, &tokens[1..]),
+        // 旧版 interzone 写法：policy source 10.1.1.0 0.0.0.255 / address-group 1 / easy-ip
+        "policy" => match tokens.get(1) {
+            Some(&"source") => rule.source_addresses.extend(address_spec(&tokens[2..])),
+            Some(&"destination") => rule
+                .destination_addresses
+                .extend(address_spec(&tokens[2..])),
+            Some(&"service") => rule.services.push(tokens[2..].join(" ")),
+            _ => {}
+        },
+        "address-group" => {
+            rule.address_group = tokens.get(1).map(|name| name.to_string());
+            if rule.action.is_empty() {
+                rule.action = "source-nat".to_string();
+            }
+        }
+        "easy-ip" => {
+            rule.easy_ip = true;
+            if let Some(interface) = tokens.get(1) {
+                rule.egress_interface = Some(interface.to_string());
+            }
+            if rule.action.is_empty() {
+                rule.action = "source-nat".to_string();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_policy_action(rule: &mut NatPolicyRule, args: &[&str]) {
+    match args.first() {
+        Some(&"source-nat") => {
+            rule.action = "source-nat".to_string();
+            match args.get(1) {
+                Some(&"address-group") => {
+                    rule.address_group = args.get(2).map(|name| name.to_string())
+                }
+                Some(&"easy-ip") => rule.easy_ip = true,
+                _ => {}
+            }
+        }
+        Some(&"no-nat") => rule.action = "no-nat".to_string(),
+        // destination-nat static address-to-address address 10.2.0.1 [10.2.0.9]
+        // destination-nat static port-to-port address 10.1.1.1 8080
+        // destination-nat address-group dnat_group [8080]
+        Some(&"destination-nat") => {
+            rule.action = "destination-nat".to_string();
+            if let Some(pos) = args.iter().position(|token| *token == "address") {
+                let start = args.get(pos + 1).copied();
+                let next = args.get(pos + 2).copied();
+                rule.translated_address = match (start, next) {
+                    (Some(start), Some(end)) if end.parse::<Ipv4Addr>().is_ok() => {
+                        Some(format!("{}-{}", start, end))
+                    }
+                    (Some(start), _) => Some(start.to_string()),
+                    _ => None,
+                };
+                rule.translated_port = next
+                    .filter(|port| is_port_number(port))
+                    .map(|port| port.to_string());
+            }
+            if let Some(pos) = args.iter().position(|token| *token == "address-group") {
+                rule.address_group = args.get(pos + 1).map(|name| name.to_string());
+                rule.translated_port = args
+                    .get(pos + 2)
+                    .filter(|port| is_port_number(port))
+                    .map(|port| port.to_string());
+            }
+        }
+        _ => rule.action = args.join(" "),
+    }
+}
+
+// 统一地址写法：10.1.1.0 24 / 10.1.1.0 mask 255.255.255.0 / 10.1.1.0 0.0.0.255 -> 10.1.1.0/24
+// 带 mask 关键字时按子网掩码解释；直接跟在地址后的点分值可能是掩码也可能是反掩码，与子网计算的判定一致
+fn address_spec(args: &[&str]) -> Option<String> {
+    let mask_prefix = |mask: &str| prefix_from_mask(u32::from(mask.parse::<Ipv4Addr>().ok()?));
+    let prefix_of = |mask: &str| {
+        classify_mask(u32::from(mask.parse::<Ipv4Addr>().ok()?)).map(|(prefix, _)| prefix)
+    };
+
+    match args {
+        ["address-set", name, ..] => Some(format!("address-set {}", name.trim_matches('"'))),
+        ["range", start, end, ..] => Some(format!("{}-{}", start, end)),
+        [ip, "mask", mask, ..] => Some(match mask_prefix(mask) {
+            Some(prefix) => format!("{}/{}", ip, prefix),
+            None => format!("{} mask {}", ip, mask),
+        }),
+        [ip, second, ..] if is_port_number(second) => Some(format!("{}/{}", ip, second)),
+        [ip, second, ..] if second.parse::<Ipv4Addr>().is_ok() => Some(match prefix_of(second) {
+            Some(prefix) => format!("{}/{}", ip, prefix),
+            None => format!("{} {}", ip, second),
+        }),
+        [ip, ..] => Some(ip.to_string()),
+        [] => None,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ServerDialect {
+    Huawei,
+    H3c,
+}
+
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl Token<'_> {
+    fn error(&self, message: impl Into<String>) -> ParseDiagnostic {
+        ParseDiagnostic::new(DiagnosticSeverity::Error, message, self.start, self.end)
+    }
+
+    fn warning(&self, message: impl Into<String>) -> ParseDiagnostic {
+        ParseDiagnostic::new(DiagnosticSeverity::Warning, message, self.start, self.end)
+    }
+}
+
+// 按空白切分，引号内的空格不切分；列号按字符计算，去掉首尾引号
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    let mut in_quote = false;
+
+    let mut column = 0;
+    for (byte, ch) in line.char_indices() {
+        if ch.is_whitespace() && !in_quote {
+            if let Some((start_byte, start_column)) = current.take() {
+                tokens.push(new_token(&line[start_byte..byte], start_column, column));
+            }
+        } else {
+            if current.is_none() {
+                current = Some((byte, column));
+            }
+            if ch == '"' {
+                in_quote = !in_quote;
+            }
+        }
+        column += 1;
+    }
+    if let Some((start_byte, start_column)) = current {
+        tokens.push(new_token(&line[start_byte..], start_column, column));
+    }
+    tokens
+}
+
+fn new_token(raw: &str, start: usize, end: usize) -> Token<'_> {
+    let text = raw
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(raw);
+    Token { text, start, end }
+}
+
+// 华为与 H3C 的 nat server 语法基本一致：华为可在关键字前带名称，H3C 以 rule/description 标识。
+// 无法识别的参数记为警告，地址、端口、协议等取值错误记为错误并使该行解析失败
+fn parse_server_command(
+    command: &str,
+    dialect: ServerDialect,
+) -> Result<NatEntry, Vec<ParseDiagnostic>> {
+    let tokens = tokenize(command);
+    let line_end = command.chars().count();
+    if tokens.len() < 2 || tokens[0].text != "nat" || tokens[1].text != "server" {
+        return Err(vec![ParseDiagnostic::new(
+            DiagnosticSeverity::Error,
+            "不是 nat server 命令",
+            0,
+            line_end,
+        )]);
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut entry = NatEntry {
+        command: command.to_string(),
+        ..Default::default()
+    };
+    let mut name = None;
+    let mut global = None;
+    let mut inside = None;
+    let mut idx = 2;
+
+    if dialect == ServerDialect::Huawei {
+        while idx < tokens.len() && !is_server_keyword(tokens[idx].text) {
+            if name.is_none() {
+                name = Some(tokens[idx].text.to_string());
+            } else {
+                diagnostics.push(tokens[idx].warning(format!("多余的参数 {}", tokens[idx].text)));
+            }
+            idx += 1;
+        }
+    }
+
+    while idx < tokens.len() {
+        let token = &tokens[idx];
+        idx += 1;
+        match token.text {
+            "global" | "inside" => {
+                let endpoint = parse_endpoint(
+                    &tokens,
+                    &mut idx,
+                    token,
+                    &mut entry.interface,
+                    &mut diagnostics,
+                );
+                if token.text == "global" {
+                    global = endpoint;
+                } else {
+                    inside = endpoint;
+                }
+            }
+            "protocol" | "zone" | "vpn-instance" | "acl" | "rule" | "vrrp" => {
+                let Some(value) = tokens
+                    .get(idx)
+                    .filter(|value| !is_server_keyword(value.text))
+                else {
+                    diagnostics.push(token.error(format!("{} 缺少取值", token.text)));
+                    continue;
+                };
+                idx += 1;
+                match token.text {
+                    "protocol" => match value.text.parse() {
+                        Ok(protocol) => entry.protocol = protocol,
+                        Err(message) => diagnostics.push(value.error(message)),
+                    },
+                    "vrrp" => match value.text.parse::<u16>() {
+                        Ok(id) => entry.vrrp = Some(id),
+                        Err(_) => diagnostics
+                            .push(value.error(format!("无效的 VRRP 备份组 {}", value.text))),
+                    },
+                    "zone" => entry.zone = Some(value.text.to_string()),
+                    // global 与 inside 两侧都可带 vpn-instance，紧跟 global 地址的归入 global 侧
+                    "vpn-instance" if global.is_some() && inside.is_none() => {
+                        entry.global_vpn_instance = Some(value.text.to_string())
+                    }
+                    "vpn-instance" => entry.vpn_instance = Some(value.text.to_string()),
+                    "acl" => entry.acl = Some(value.text.to_string()),
+                    _ => entry.rule = Some(value.text.to_string()),
+                }
+            }
+            // 描述可含空格，直到行尾或 counting/reversible/disable
+            "description" => {
+                let end = tokens[idx..]
+                    .iter()
+                    .position(|token| matches!(token.text, "counting" | "reversible" | "disable"))
+                    .map_or(tokens.len(), |pos| idx + pos);
+                let words: Vec<&str> = tokens[idx..end].iter().map(|token| token.text).collect();
+                if words.is_empty() {
+                    diagnostics.push(token.error("description 缺少取值"));
+                } else {
+                    entry.description = Some(words.join(" "));
+                }
+                idx = end;
+            }
+            "no-reverse" => entry.no_reverse = true,
+            "reversible" => entry.reversible = true,
+            "counting" => entry.counting = true,
+            "unr-route" | "disable" => {}
+            _ => diagnostics.push(token.warning(format!("无法识别的参数 {}", token.text))),
+        }
+    }
+
+    let has_error = |diagnostics: &[ParseDiagnostic]| {
+        diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+    };
+    let (Some((global_ip, global_port)), Some((inside_ip, inside_port))) = (global, inside) else {
+        if !has_error(&diagnostics) {
+            diagnostics.push(ParseDiagnostic::new(
+                DiagnosticSeverity::Error,
+                "缺少 global 或 inside 地址",
+                0,
+                line_end,
+            ));
+        }
+        return Err(diagnostics);
+    };
+    if has_error(&diagnostics) {
+        return Err(diagnostics);
+    }
+
+    entry.global_ip = global_ip;
+    entry.global_port = global_port;
+    entry.inside_ip = inside_ip;
+    entry.inside_port = inside_port;
+    entry.name = match dialect {
+        ServerDialect::Huawei => name.unwrap_or_else(|| {
+            format!(
+                "{} {}:{} -> {}:{}",
+                entry.protocol,
+                entry.global_ip,
+                format_port(&entry.global_port),
+                entry.inside_ip,
+                format_port(&entry.inside_port)
+            )
+        }),
+        ServerDialect::H3c => entry.rule.clone().unwrap_or_else(|| "-".to_string()),
+    };
+    // 华为未配置 no-reverse 时允许内部主机主动使用公网地址，与 H3C 的 reversible 含义相同
+    if dialect == ServerDialect::Huawei {
+        entry.reversible = !entry.no_reverse;
+    }
+    entry.diagnostics = diagnostics;
+    Ok(entry)
+}
+
+// global/inside 后为 地址 [结束地址] [端口 [结束端口]]，也可以是 current-interface / interface X
+fn parse_endpoint(
+    tokens: &[Token],
+    idx: &mut usize,
+    keyword: &Token,
+    interface: &mut Option<String>,
+    diagnostics: &mut Vec<ParseDiagnostic>,
+) -> Option<(NatAddress, Option<PortRange>)> {
+    let Some(first) = tokens.get(*idx) else {
+        diagnostics.push(keyword.error(format!("{} 缺少地址", keyword.text)));
+        return None;
+    };
+    *idx += 1;
+
+    let address = match first.text {
+        "current-interface" => NatAddress::Interface,
+        "interface" => {
+            let Some(name) = tokens.get(*idx) else {
+                diagnostics.push(first.error("interface 缺少接口名称"));
+                return None;
+            };
+            *idx += 1;
+            *interface = Some(name.text.to_string());
+            NatAddress::Interface
+        }
+        text => match text.parse::<IpAddr>() {
+            Ok(start) => match tokens.get(*idx).and_then(|next| next.text.parse().ok()) {
+                Some(end) => {
+                    *idx += 1;
+                    NatAddress::Range(start, end)
+                }
+                None => NatAddress::Ip(start),
+            },
+            Err(_) => {
+                diagnostics.push(first.error(format!("无效的 IP 地址 {}", text)));
+                return None;
+            }
+        },
+    };
+
+    let mut ports = Vec::new();
+    while ports.len() < 2 {
+        let Some(token) = tokens
+            .get(*idx)
+            .filter(|token| !is_server_keyword(token.text))
+        else {
+            break;
+        };
+        match port_value(token.text) {
+            Some(port) => ports.push((port, token)),
+            None if is_port_number(token.text) => {
+                diagnostics.push(token.error(format!("端口 {} 不在 1-65535 范围内", token.text)));
+                return None;
+            }
+            None => break,
+        }
+        *idx += 1;
+    }
+
+    let port = match ports.as_slice() {
+        [] => None,
+        [(port, _)] => Some(PortRange {
+            start: *port,
+            end: *port,
+        }),
+        [(start, _), (end, _)] if start <= end => Some(PortRange {
+            start: *start,
+            end: *end,
+        }),
+        [_, (_, end_token)] => {
+            diagnostics.push(end_token.error("结束端口小于起始端口"));
+            return None;
+        }
+        _ => None,
+    };
+    Some((address, port))
 }
 
-fn is_huawei_keyword(token: &str) -> bool {
+fn is_server_keyword(token: &str) -> bool {
     matches!(
         token,
         "protocol"
@@ -349,9 +1758,36 @@ fn is_huawei_keyword(token: &str) -> bool {
             | "reversible"
             | "unr-route"
             | "vpn-instance"
+            | "vrrp"
+            | "rule"
+            | "description"
+            | "counting"
+            | "disable"
     )
 }
 
+// 数字端口或常见服务名（华为、Cisco 配置中均可能出现）
+fn port_value(token: &str) -> Option<u16> {
+    if is_port_number(token) {
+        return token.parse::<u16>().ok().filter(|port| *port > 0);
+    }
+    let port = match token {
+        "ftp" => 21,
+        "ssh" => 22,
+        "telnet" => 23,
+        "smtp" => 25,
+        "domain" | "dns" => 53,
+        "www" | "http" => 80,
+        "pop3" => 110,
+        "imap4" => 143,
+        "snmp" => 161,
+        "https" => 443,
+        "sqlnet" => 1521,
+        _ => return None,
+    };
+    Some(port)
+}
+
 fn is_port_number(token: &str) -> bool {
     token.chars().all(|c| c.is_ascii_digit())
 }
@@ -366,11 +1802,11 @@ mod tests {
         let entry = NATParser::parse_huawei_command(command).expect("should parse");
 
         assert_eq!(entry.name, "web service");
-        assert_eq!(entry.protocol, "tcp");
-        assert_eq!(entry.global_ip, "202.100.10.1");
-        assert_eq!(entry.global_port, "80");
-        assert_eq!(entry.inside_ip, "192.168.1.100");
-        assert_eq!(entry.inside_port, "8080");
+        assert_eq!(entry.protocol, NatProtocol::Tcp);
+        assert_eq!(entry.global_ip.to_string(), "202.100.10.1");
+        assert_eq!(format_port(&entry.global_port), "80");
+        assert_eq!(entry.inside_ip.to_string(), "192.168.1.100");
+        assert_eq!(format_port(&entry.inside_port), "8080");
     }
 
     #[test]
@@ -379,9 +1815,9 @@ mod tests {
         let entry = NATParser::parse_huawei_command(command).expect("should parse");
 
         assert_eq!(entry.name, "web");
-        assert_eq!(entry.protocol, "any");
-        assert_eq!(entry.global_port, "any");
-        assert_eq!(entry.inside_port, "any");
+        assert_eq!(entry.protocol, NatProtocol::Any);
+        assert_eq!(format_port(&entry.global_port), "any");
+        assert_eq!(format_port(&entry.inside_port), "any");
     }
 
     #[test]
@@ -390,11 +1826,11 @@ mod tests {
             "nat server protocol tcp global 202.100.10.1 80 inside 192.168.1.100 8080 no-reverse";
         let entry = NATParser::parse_huawei_command(command).expect("should parse");
 
-        assert_eq!(entry.protocol, "tcp");
-        assert_eq!(entry.global_ip, "202.100.10.1");
-        assert_eq!(entry.global_port, "80");
-        assert_eq!(entry.inside_ip, "192.168.1.100");
-        assert_eq!(entry.inside_port, "8080");
+        assert_eq!(entry.protocol, NatProtocol::Tcp);
+        assert_eq!(entry.global_ip.to_string(), "202.100.10.1");
+        assert_eq!(format_port(&entry.global_port), "80");
+        assert_eq!(entry.inside_ip.to_string(), "192.168.1.100");
+        assert_eq!(format_port(&entry.inside_port), "8080");
     }
 
     #[test]
@@ -402,9 +1838,309 @@ mod tests {
         let command = "nat server YD_UDP10.157.50.178:13102-13109 protocol udp global 183.215.36.4 13102 13109 inside 172.30.208.198 13102 13109 no-reverse";
         let entry = NATParser::parse_huawei_command(command).expect("should parse");
 
-        assert_eq!(entry.protocol, "udp");
-        assert_eq!(entry.global_port, "13102-13109");
-        assert_eq!(entry.inside_port, "13102-13109");
+        assert_eq!(entry.protocol, NatProtocol::Udp);
+        assert_eq!(format_port(&entry.global_port), "13102-13109");
+        assert_eq!(format_port(&entry.inside_port), "13102-13109");
+    }
+
+    #[test]
+    fn parse_huawei_nat_policy_and_address_groups() {
+        let config = r#"
+nat address-group snat_pool 0
+ mode pat
+ route enable
+ section 0 202.1.1.10 202.1.1.15
+destination-nat address-group dnat_pool 1
+ section 10.2.0.10 10.2.0.11
+#
+nat-policy
+ rule name "outbound web"
+  source-zone trust
+  destination-zone untrust
+  source-address 10.1.1.0 mask 255.255.255.0
+  source-address address-set office
+  action source-nat address-group snat_pool
+ rule name dnat_web
+  source-zone untrust
+  destination-address 202.1.1.20 32
+  service http
+  action destination-nat static port-to-port address 10.2.0.10 8080
+ rule name no_nat
+  disable
+  action no-nat
+#
+nat server web global 202.1.1.30 inside 10.2.0.30
+"#;
+        let result = NATParser::parse_config(config, "huawei");
+
+        assert_eq!(result.success_entries.len(), 1);
+        assert!(result.failed_entries.is_empty());
+        assert_eq!(result.address_groups.len(), 2);
+        assert_eq!(result.address_groups[0].mode.as_deref(), Some("pat"));
+        assert_eq!(
+            result.address_groups[0].sections,
+            vec!["202.1.1.10-202.1.1.15"]
+        );
+        assert_eq!(result.address_groups[1].kind, "destination");
+
+        assert_eq!(result.policy_rules.len(), 3);
+        let snat = &result.policy_rules[0];
+        assert_eq!(snat.name, "outbound web");
+        assert_eq!(snat.action, "source-nat");
+        assert_eq!(snat.address_group.as_deref(), Some("snat_pool"));
+        assert_eq!(
+            snat.source_addresses,
+            vec!["10.1.1.0/24", "address-set office"]
+        );
+        assert_eq!(snat.context.len(), 7);
+
+        let dnat = &result.policy_rules[1];
+        assert_eq!(dnat.action, "destination-nat");
+        assert_eq!(dnat.destination_addresses, vec!["202.1.1.20/32"]);
+        assert_eq!(dnat.translated_address.as_deref(), Some("10.2.0.10"));
+        assert_eq!(dnat.translated_port.as_deref(), Some("8080"));
+        assert!(result.policy_rules[2].disabled);
+    }
+
+    #[test]
+    fn parse_huawei_legacy_interzone_policy() {
+        let config = "nat address-group 1 202.1.1.2 202.1.1.5\nnat-policy interzone trust untrust outbound\npolicy 0\naction source-nat\npolicy source 10.1.1.0 0.0.0.255\naddress-group 1";
+        let result = NATParser::parse_config(config, "huawei");
+
+        assert_eq!(
+            result.address_groups[0].sections,
+            vec!["202.1.1.2-202.1.1.5"]
+        );
+        let rule = &result.policy_rules[0];
+        assert_eq!(rule.name, "interzone trust untrust outbound policy 0");
+        assert_eq!(rule.source_zones, vec!["trust"]);
+        assert_eq!(rule.source_addresses, vec!["10.1.1.0/24"]);
+        assert_eq!(rule.address_group.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn address_spec_resolves_ambiguous_zero_mask_as_host() {
+        assert_eq!(
+            address_spec(&["10.1.1.1", "0.0.0.0"]).as_deref(),
+            Some("10.1.1.1/32")
+        );
+        assert_eq!(
+            address_spec(&["10.1.1.0", "0.0.0.255"]).as_deref(),
+            Some("10.1.1.0/24")
+        );
+        assert_eq!(
+            address_spec(&["10.1.1.0", "255.255.255.0"]).as_deref(),
+            Some("10.1.1.0/24")
+        );
+        assert_eq!(
+            address_spec(&["0.0.0.0", "mask", "0.0.0.0"]).as_deref(),
+            Some("0.0.0.0/0")
+        );
+    }
+
+    #[test]
+    fn parse_h3c_outbound_static_and_address_groups() {
+        let config = r#"
+nat address-group 1
+ address 202.1.1.10 202.1.1.20
+ port-range 1024 65535
+#
+nat static outbound 10.1.1.5 202.1.1.5
+nat static outbound net-to-net 10.1.2.1 10.1.2.255 global 202.1.2.0 24 acl 3001
+#
+interface GigabitEthernet1/0/1
+ ip address 202.1.1.1 255.255.255.0
+ nat outbound 3000 address-group 1 no-pat reversible
+ nat outbound 3002
+ nat server protocol tcp global 202.1.1.30 80 inside 10.1.1.30 8080
+ nat static enable
+#
+"#;
+        let result = NATParser::parse_config(config, "h3c");
+
+        assert!(result.failed_entries.is_empty());
+        assert_eq!(
+            result.address_groups[0].sections,
+            vec!["202.1.1.10-202.1.1.20"]
+        );
+
+        assert_eq!(result.policy_rules.len(), 2);
+        let pooled = &result.policy_rules[0];
+        assert_eq!(pooled.acl.as_deref(), Some("3000"));
+        assert_eq!(pooled.address_group.as_deref(), Some("1"));
+        assert_eq!(pooled.options, vec!["no-pat", "reversible"]);
+        assert_eq!(
+            pooled.egress_interface.as_deref(),
+            Some("GigabitEthernet1/0/1")
+        );
+        assert!(result.policy_rules[1].easy_ip);
+
+        assert_eq!(
+            result.success_entries[0].scope_interface.as_deref(),
+            Some("GigabitEthernet1/0/1")
+        );
+
+        assert_eq!(result.static_mappings.len(), 2);
+        assert_eq!(result.static_mappings[0].global_address, "202.1.1.5");
+        let net = &result.static_mappings[1];
+        assert_eq!(net.local_address, "10.1.2.1-10.1.2.255");
+        assert_eq!(net.global_address, "202.1.2.0/24");
+        assert_eq!(net.acl.as_deref(), Some("3001"));
+        assert_eq!(net.interfaces, vec!["GigabitEthernet1/0/1"]);
+    }
+
+    #[test]
+    fn parse_h3c_outbound_named_address_group() {
+        let config =
+            "interface GigabitEthernet1/0/1\n nat outbound name acl_out address-group name pool1";
+        let result = NATParser::parse_config(config, "h3c");
+
+        let rule = &result.policy_rules[0];
+        assert_eq!(rule.address_group.as_deref(), Some("pool1"));
+        assert_eq!(rule.acl.as_deref(), Some("acl_out"));
+        assert!(!rule.easy_ip);
+
+        let rule = parse_h3c_outbound("nat outbound 3000 address-group name pool1", None).unwrap();
+        assert_eq!(rule.address_group.as_deref(), Some("pool1"));
+        assert!(rule.options.is_empty());
+    }
+
+    #[test]
+    fn parse_h3c_interface_global_addresses() {
+        let config = "nat server protocol tcp global interface GigabitEthernet1/0/2 80 inside 10.0.0.5 80\n\
+                      nat server protocol tcp global current-interface 81 inside 10.0.0.5 81\n\
+                      interface GigabitEthernet1/0/1\n \
+                      nat server protocol tcp global interface GigabitEthernet1/0/2 82 inside 10.0.0.5 82\n \
+                      nat server protocol tcp global current-interface 83 inside 10.0.0.5 83";
+        let result = NATParser::parse_config(config, "h3c");
+        assert!(result.failed_entries.is_empty());
+        let interfaces: Vec<(NatAddress, Option<&str>, Option<&str>)> = result
+            .success_entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.global_ip.clone(),
+                    entry.interface.as_deref(),
+                    entry.scope_interface.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            interfaces,
+            vec![
+                (NatAddress::Interface, Some("GigabitEthernet1/0/2"), None),
+                (NatAddress::Interface, None, None),
+                (
+                    NatAddress::Interface,
+                    Some("GigabitEthernet1/0/2"),
+                    Some("GigabitEthernet1/0/1")
+                ),
+                (NatAddress::Interface, None, Some("GigabitEthernet1/0/1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_cisco_asa_object_and_twice_nat() {
+        let config = r#"
+object network WEB_REAL
+ host 10.1.1.10
+ description Web server
+object network WEB_PUBLIC
+ host 203.0.113.10
+object network INSIDE_NET
+ subnet 10.1.0.0 255.255.0.0
+object service HTTPS_REAL
+ service tcp source eq 8443
+object service HTTPS_PUBLIC
+ service tcp source eq https
+object network WEB_REAL
+ nat (inside,outside) static WEB_PUBLIC service tcp www 8080
+object network INSIDE_NET
+ nat (inside,outside) dynamic interface
+nat (dmz,outside) source static WEB_REAL WEB_PUBLIC service HTTPS_REAL HTTPS_PUBLIC description dmz https
+"#;
+        let result = NATParser::parse_config(config, "cisco-asa");
+
+        assert!(result.failed_entries.is_empty());
+        let object_nat = &result.success_entries[0];
+        assert_eq!(object_nat.name, "WEB_REAL");
+        assert_eq!(object_nat.global_ip.to_string(), "203.0.113.10");
+        assert_eq!(format_port(&object_nat.global_port), "8080");
+        assert_eq!(format_port(&object_nat.inside_port), "80");
+        assert_eq!(object_nat.description.as_deref(), Some("Web server"));
+
+        let twice_nat = &result.success_entries[1];
+        assert_eq!(format_port(&twice_nat.inside_port), "8443");
+        assert_eq!(format_port(&twice_nat.global_port), "443");
+        assert_eq!(twice_nat.description.as_deref(), Some("dmz https"));
+
+        assert!(result.policy_rules[0].easy_ip);
+        assert_eq!(result.policy_rules[0].source_addresses, vec!["10.1.0.0/16"]);
+    }
+
+    #[test]
+    fn parse_cisco_ios_and_fortigate() {
+        let ios = "ip nat pool OUT 203.0.113.20 203.0.113.30 netmask 255.255.255.0\n\
+                   ip nat inside source list 10 pool OUT overload\n\
+                   ip nat inside source static tcp 10.1.1.10 80 interface GigabitEthernet0/0 8080\n\
+                   ip nat inside source static 10.1.1.11 203.0.113.11";
+        let result = NATParser::parse_config(ios, "cisco-ios");
+        assert!(result.failed_entries.is_empty());
+        assert_eq!(
+            result.address_groups[0].sections,
+            vec!["203.0.113.20-203.0.113.30"]
+        );
+        assert_eq!(result.policy_rules[0].acl.as_deref(), Some("10"));
+        assert_eq!(result.success_entries[0].global_ip.to_string(), "interface");
+        assert_eq!(format_port(&result.success_entries[0].global_port), "8080");
+        assert_eq!(
+            result.success_entries[1].global_ip.to_string(),
+            "203.0.113.11"
+        );
+
+        let fortigate = r#"
+config firewall vip
+    edit "web_vip"
+        set comment "Web server"
+        set extip 203.0.113.10
+        set mappedip "10.1.1.10"
+        set extintf "port1"
+        set portforward enable
+        set extport 443
+        set mappedport 8443
+    next
+end
+"#;
+        let result = NATParser::parse_config(fortigate, "fortigate");
+        let entry = &result.success_entries[0];
+        assert_eq!(entry.name, "web_vip");
+        assert_eq!(entry.protocol, NatProtocol::Tcp);
+        assert_eq!(entry.inside_ip.to_string(), "10.1.1.10");
+        assert_eq!(format_port(&entry.inside_port), "8443");
+        assert_eq!(entry.scope_interface.as_deref(), Some("port1"));
+        assert_eq!(entry.description.as_deref(), Some("Web server"));
+    }
+
+    #[test]
+    fn parse_auto_detects_vendor() {
+        let config = "nat server protocol tcp global 203.0.113.1 80 inside 10.0.0.5 8080 vrrp 1 description web counting";
+        let result = NATParser::parse_config(config, "auto");
+
+        assert_eq!(result.device_type, "h3c");
+        assert_eq!(result.success_entries.len(), 1);
+        assert_eq!(result.detection.unwrap().confidence, 1.0);
+    }
+
+    #[test]
+    fn parse_auto_falls_back_for_plain_nat_server() {
+        let config = "nat server protocol tcp global 203.0.113.1 80 inside 10.0.0.5 8080";
+        let result = NATParser::parse_config(config, "auto");
+
+        assert_eq!(result.device_type, "unknown");
+        assert_eq!(result.success_entries.len(), 1);
+        assert!(result.failed_entries.is_empty());
+        assert_eq!(result.success_entries[0].inside_port.unwrap().start, 8080);
     }
 
     #[test]
@@ -412,13 +2148,29 @@ mod tests {
         let command = "nat server protocol tcp global 203.0.113.1 80 inside 10.0.0.5 8080 rule 100 vrrp 1 description Web Service counting";
         let entry = NATParser::parse_h3c_command(command).expect("should parse");
 
-        assert_eq!(entry.protocol, "tcp");
-        assert_eq!(entry.global_ip, "203.0.113.1");
-        assert_eq!(entry.global_port, "80");
-        assert_eq!(entry.inside_ip, "10.0.0.5");
-        assert_eq!(entry.inside_port, "8080");
+        assert_eq!(entry.protocol, NatProtocol::Tcp);
+        assert_eq!(entry.global_ip.to_string(), "203.0.113.1");
+        assert_eq!(format_port(&entry.global_port), "80");
+        assert_eq!(entry.inside_ip.to_string(), "10.0.0.5");
+        assert_eq!(format_port(&entry.inside_port), "8080");
         assert_eq!(entry.rule.as_deref(), Some("100"));
-        assert_eq!(entry.vrrp.as_deref(), Some("1"));
+        assert_eq!(entry.vrrp, Some(1));
         assert_eq!(entry.description.as_deref(), Some("Web Service"));
     }
+
+    #[test]
+    fn reports_diagnostics_with_columns() {
+        let command = "nat server protocol udp global 203.0.113.2 53 inside 10.0.0.6 53 fast";
+        let entry = NATParser::parse_h3c_command(command).expect("should parse");
+        assert_eq!(entry.diagnostics.len(), 1);
+        assert_eq!(entry.diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(entry.diagnostics[0].start, command.find("fast").unwrap());
+
+        let config = "nat server protocol tcp global 203.0.113.1 70000 inside 10.0.0.5 80";
+        let result = NATParser::parse_config(config, "h3c");
+        assert_eq!(result.failed_entries, vec![config]);
+        let diagnostic = &result.failed_details[0].diagnostics[0];
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!((diagnostic.start, diagnostic.end), (43, 48));
+    }
 }
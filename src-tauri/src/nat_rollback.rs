@@ -0,0 +1,175 @@
+use crate::nat_parser::{NatAddress, NatEntry, NatParseResult};
+use crate::nat_translator::{
+    port_part, protocol_part, quote_name, vpn_part, NatTranslationIssue, TargetDevice,
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NatRollbackResult {
+    pub device_type: TargetDevice,
+    pub commands: Vec<String>,
+    // 无法自动生成回退命令的条目
+    pub skipped: Vec<NatTranslationIssue>,
+}
+
+#[tauri::command]
+pub fn generate_nat_rollback(result: NatParseResult) -> Result<NatRollbackResult, String> {
+    let device = match result.device_type.as_str() {
+        "huawei" => TargetDevice::Huawei,
+        "h3c" => TargetDevice::H3c,
+        _ => return Err("仅支持为华为、H3C 配置生成回退命令".to_string()),
+    };
+    let rollback = rollback_script(&result.success_entries, device);
+    if rollback.commands.is_empty() && !rollback.skipped.is_empty() {
+        let details: Vec<String> = rollback
+            .skipped
+            .iter()
+            .map(|issue| format!("{}：{}", issue.source, issue.reason))
+            .collect();
+        return Err(format!(
+            "{} 条映射均无法生成回退命令：\n{}",
+            details.len(),
+            details.join("\n")
+        ));
+    }
+    Ok(rollback)
+}
+
+// 按与下发相反的顺序回退
+pub(crate) fn rollback_script(entries: &[NatEntry], device: TargetDevice) -> NatRollbackResult {
+    let mut rollback = NatRollbackResult {
+        device_type: device,
+        commands: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for entry in entries.iter().rev() {
+        let lines = match device {
+            TargetDevice::Huawei => huawei_undo(entry),
+            TargetDevice::H3c => h3c_undo(entry),
+        };
+        match lines {
+            Ok(lines) => rollback.commands.extend(lines),
+            Err(reason) => rollback.skipped.push(NatTranslationIssue {
+                source: entry.command.clone(),
+                reason,
+            }),
+        }
+    }
+    rollback
+}
+
+// 华为按名称删除；解析时为未命名条目生成的名称不会出现在原命令中
+fn huawei_undo(entry: &NatEntry) -> Result<Vec<String>, String> {
+    let name = entry.name.trim();
+    if name.is_empty() || !entry.command.contains(name) {
+        return Err(
+            "原命令未配置名称，需在设备上 display nat server 查询自动生成的名称后回退".to_string(),
+        );
+    }
+    Ok(vec![format!("undo nat server name {}", quote_name(name))])
+}
+
+// H3C 需完整匹配协议、地址、端口、VPN 实例与 VRRP 备份组；接口下的配置需进入接口视图
+fn h3c_undo(entry: &NatEntry) -> Result<Vec<String>, String> {
+    let mut command = "undo nat server".to_string();
+    command.push_str(&protocol_part(entry));
+    command.push_str(&format!(" global {}", h3c_global(entry)?));
+    command.push_str(&port_part(&entry.global_port));
+    command.push_str(&vpn_part(&entry.global_vpn_instance));
+    command.push_str(&format!(" inside {}", address_text(&entry.inside_ip)?));
+    command.push_str(&port_part(&entry.inside_port));
+    command.push_str(&vpn_part(&entry.vpn_instance));
+    if let Some(acl) = &entry.acl {
+        command.push_str(&format!(" acl {}", acl));
+    }
+    if let Some(vrrp) = entry.vrrp {
+        command.push_str(&format!(" vrrp {}", vrrp));
+    }
+
+    Ok(match &entry.scope_interface {
+        Some(interface) => vec![
+            format!("interface {}", interface),
+            format!(" {}", command),
+            "quit".to_string(),
+        ],
+        None => vec![command],
+    })
+}
+
+// 按原命令的写法还原 global 侧：current-interface、interface X 或地址
+fn h3c_global(entry: &NatEntry) -> Result<String, String> {
+    match (&entry.global_ip, &entry.interface) {
+        (NatAddress::Interface, Some(interface)) => Ok(format!("interface {}", interface)),
+        (NatAddress::Interface, None) => Ok("current-interface".to_string()),
+        (address, _) => address_text(address),
+    }
+}
+
+fn address_text(address: &NatAddress) -> Result<String, String> {
+    match address {
+        NatAddress::Ip(ip) => Ok(ip.to_string()),
+        NatAddress::Range(start, end) => Ok(format!("{} {}", start, end)),
+        other => Err(format!("无法按 {} 生成回退命令", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nat_parser::NATParser;
+
+    #[test]
+    fn builds_vendor_rollback_commands() {
+        let huawei = "nat server \"web server\" protocol tcp global 203.0.113.1 80 inside 10.0.0.5 8080 no-reverse\n\
+                      nat server protocol udp global 203.0.113.2 53 inside 10.0.0.6 53";
+        let rollback = generate_nat_rollback(NATParser::parse_config(huawei, "huawei")).unwrap();
+        assert_eq!(
+            rollback.commands,
+            vec!["undo nat server name \"web server\""]
+        );
+        assert_eq!(rollback.skipped.len(), 1);
+
+        let h3c = "interface GigabitEthernet1/0/1\n\
+                   nat server protocol tcp global 203.0.113.1 8000 8010 inside 10.0.0.5 8000 8010 vrrp 1 description web counting";
+        let rollback = generate_nat_rollback(NATParser::parse_config(h3c, "h3c")).unwrap();
+        assert_eq!(
+            rollback.commands,
+            vec![
+                "interface GigabitEthernet1/0/1",
+                " undo nat server protocol tcp global 203.0.113.1 8000 8010 inside 10.0.0.5 8000 8010 vrrp 1",
+                "quit",
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_original_global_token_in_h3c_rollback() {
+        let h3c = "nat server protocol tcp global interface GigabitEthernet1/0/2 8080 vpn-instance pub inside 10.0.0.5 80 vpn-instance priv\n\
+                   interface GigabitEthernet1/0/1\n\
+                   nat server protocol tcp global current-interface 443 inside 10.0.0.6 443\n\
+                   nat server protocol udp global interface LoopBack0 53 inside 10.0.0.7 53";
+        let rollback = generate_nat_rollback(NATParser::parse_config(h3c, "h3c")).unwrap();
+        assert_eq!(
+            rollback.commands,
+            vec![
+                "interface GigabitEthernet1/0/1",
+                " undo nat server protocol udp global interface LoopBack0 53 inside 10.0.0.7 53",
+                "quit",
+                "interface GigabitEthernet1/0/1",
+                " undo nat server protocol tcp global current-interface 443 inside 10.0.0.6 443",
+                "quit",
+                "undo nat server protocol tcp global interface GigabitEthernet1/0/2 8080 vpn-instance pub inside 10.0.0.5 80 vpn-instance priv",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_entries_without_rollback() {
+        let huawei = "nat server protocol udp global 203.0.113.2 53 inside 10.0.0.6 53";
+        let Err(err) = generate_nat_rollback(NATParser::parse_config(huawei, "huawei")) else {
+            panic!("未命名条目不应生成回退命令");
+        };
+        assert!(err.contains("203.0.113.2") && err.contains("display nat server"));
+    }
+}
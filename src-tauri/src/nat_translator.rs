@@ -0,0 +1,501 @@
+use crate::nat_batch_generator::PortRange;
+use crate::nat_parser::{
+    format_port, NatAddress, NatAddressGroup, NatEntry, NatParseResult, NatProtocol,
+};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetDevice {
+    Huawei,
+    H3c,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NatTranslationIssue {
+    pub source: String,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NatTranslationResult {
+    pub source_device: String,
+    pub target_device: TargetDevice,
+    pub commands: Vec<String>,
+    // 已生成命令但有属性无法对应
+    pub warnings: Vec<NatTranslationIssue>,
+    // 完全无法转换，需要手工处理
+    pub untranslated: Vec<NatTranslationIssue>,
+}
+
+#[tauri::command]
+pub fn translate_nat_config(
+    result: NatParseResult,
+    target: TargetDevice,
+) -> Result<NatTranslationResult, String> {
+    // 混合粘贴或无法识别的配置中各条目的语法来源不确定，不能直接转换
+    if matches!(result.device_type.as_str(), "mixed" | "unknown" | "") {
+        return Err("无法确定源设备类型，请指定厂商或拆分混合配置后再转换".to_string());
+    }
+    let same_vendor = match target {
+        TargetDevice::Huawei => result.device_type == "huawei",
+        TargetDevice::H3c => result.device_type == "h3c",
+    };
+    if same_vendor {
+        return Err("源设备与目标设备相同，无需转换".to_string());
+    }
+
+    let mut translation = NatTranslationResult {
+        source_device: result.device_type.clone(),
+        target_device: target,
+        commands: Vec::new(),
+        warnings: Vec::new(),
+        untranslated: Vec::new(),
+    };
+
+    // H3C 地址组以数字编号：数字名称沿用原编号，其余名称分配未被占用的编号
+    let mut used_ids: Vec<u32> = result
+        .address_groups
+        .iter()
+        .filter_map(|group| group.name.parse().ok())
+        .collect();
+    for group in &result.address_groups {
+        let id = match group.name.parse::<u32>() {
+            Ok(id) => id,
+            Err(_) => {
+                let id = (1..).find(|id| !used_ids.contains(id)).unwrap_or_default();
+                used_ids.push(id);
+                id
+            }
+        };
+        translate_address_group(group, id, target, &mut translation);
+    }
+
+    for entry in &result.success_entries {
+        let command = match target {
+            TargetDevice::Huawei => to_huawei_server(entry, &mut translation),
+            TargetDevice::H3c => to_h3c_server(entry, &mut translation),
+        };
+        match command {
+            Ok(lines) => translation.commands.extend(lines),
+            Err(reason) => translation.untranslated.push(NatTranslationIssue {
+                source: entry.command.clone(),
+                reason,
+            }),
+        }
+    }
+
+    // 华为按安全区域写 nat-policy，H3C 按接口 + ACL 写 nat outbound，源地址与 ACL 无法一一对应
+    for rule in &result.policy_rules {
+        translation.untranslated.push(NatTranslationIssue {
+            source: rule.context.join("\n"),
+            reason: "源 NAT 策略依赖安全区域/ACL，需按目标设备手工重建".to_string(),
+        });
+    }
+    for mapping in &result.static_mappings {
+        translation.untranslated.push(NatTranslationIssue {
+            source: mapping.command.clone(),
+            reason: "nat static 一对一映射暂不支持自动转换".to_string(),
+        });
+    }
+    for line in &result.failed_entries {
+        translation.untranslated.push(NatTranslationIssue {
+            source: line.clone(),
+            reason: "原配置未能解析".to_string(),
+        });
+    }
+
+    Ok(translation)
+}
+
+fn translate_address_group(
+    group: &NatAddressGroup,
+    h3c_id: u32,
+    target: TargetDevice,
+    translation: &mut NatTranslationResult,
+) {
+    let source = group.context.join("\n");
+    if group.kind != "source" || group.sections.is_empty() {
+        translation.untranslated.push(NatTranslationIssue {
+            source,
+            reason: "仅支持转换带地址段的源 NAT 地址池".to_string(),
+        });
+        return;
+    }
+
+    match target {
+        TargetDevice::Huawei => {
+            translation
+                .commands
+                .push(format!("nat address-group {}", quote_name(&group.name)));
+            match group.mode.as_deref().map(huawei_group_mode) {
+                Some(Some(mode)) => translation.commands.push(format!(" mode {}", mode)),
+                Some(None) => translation.warnings.push(NatTranslationIssue {
+                    source: source.clone(),
+                    reason: format!(
+                        "地址池模式 {} 无法对应华为的 pat/no-pat，已忽略",
+                        group.mode.as_deref().unwrap_or_default()
+                    ),
+                }),
+                None => {}
+            }
+            for (section_idx, section) in group.sections.iter().enumerate() {
+                let (start, end) = section.split_once('-').unwrap_or((section, section));
+                translation
+                    .commands
+                    .push(format!(" section {} {} {}", section_idx, start, end));
+            }
+        }
+        // 非数字名称用 name 关键字保留
+        TargetDevice::H3c => {
+            if group.name.parse::<u32>().is_ok() {
+                translation
+                    .commands
+                    .push(format!("nat address-group {}", h3c_id));
+            } else {
+                translation.commands.push(format!(
+                    "nat address-group {} name {}",
+                    h3c_id,
+                    group.name.replace(' ', "_")
+                ));
+            }
+            for section in &group.sections {
+                let (start, end) = section.split_once('-').unwrap_or((section, section));
+                translation
+                    .commands
+                    .push(format!(" address {} {}", start, end));
+            }
+            if group.mode.is_some() {
+                translation.warnings.push(NatTranslationIssue {
+                    source,
+                    reason: "H3C 在 nat outbound 上指定 no-pat，地址池 mode 已忽略".to_string(),
+                });
+            }
+        }
+    }
+}
+
+// 华为 mode 取值为 pat / no-pat [global|local]；FortiGate ippool 的 overload / one-to-one 按含义对应
+fn huawei_group_mode(mode: &str) -> Option<&str> {
+    match mode {
+        "pat" | "overload" => Some("pat"),
+        "no-pat" | "one-to-one" => Some("no-pat"),
+        "no-pat global" | "no-pat local" => Some(mode),
+        _ => None,
+    }
+}
+
+fn to_huawei_server(
+    entry: &NatEntry,
+    translation: &mut NatTranslationResult,
+) -> Result<Vec<String>, String> {
+    let mut dropped = Vec::new();
+    let mut command = format!("nat server {}", quote_name(&server_label(entry)));
+    if let Some(zone) = &entry.zone {
+        command.push_str(&format!(" zone {}", zone));
+    }
+    command.push_str(&protocol_part(entry));
+    command.push_str(&format!(
+        " global {}",
+        global_address(entry, TargetDevice::Huawei)?
+    ));
+    command.push_str(&port_part(&entry.global_port));
+    command.push_str(&vpn_part(&entry.global_vpn_instance));
+    command.push_str(&format!(" inside {}", inside_address(entry)?));
+    command.push_str(&port_part(&entry.inside_port));
+    command.push_str(&vpn_part(&entry.vpn_instance));
+    if let Some(vrrp) = entry.vrrp {
+        command.push_str(&format!(" vrrp {}", vrrp));
+    }
+    if !entry.reversible {
+        command.push_str(" no-reverse");
+    }
+
+    // current-interface 已转换为 global interface <所在接口>，其余情况无法保留所在接口
+    if let Some(interface) = &entry.scope_interface {
+        if entry.global_ip != NatAddress::Interface || entry.interface.is_some() {
+            dropped.push(format!("所属接口 {}（华为按 zone 限定）", interface));
+        }
+    }
+    if let Some(acl) = &entry.acl {
+        dropped.push(format!("ACL {}", acl));
+    }
+    if entry.counting {
+        dropped.push("counting".to_string());
+    }
+    if let (Some(_), Some(rule)) = (present(&entry.description), present(&entry.rule)) {
+        dropped.push(format!("rule 名称 {}", rule));
+    }
+    push_dropped(entry, dropped, translation);
+
+    Ok(vec![command])
+}
+
+// H3C 的 nat server 配置在接口视图下，所在接口未知时只输出命令并提示
+fn to_h3c_server(
+    entry: &NatEntry,
+    translation: &mut NatTranslationResult,
+) -> Result<Vec<String>, String> {
+    let mut dropped = Vec::new();
+    let mut command = "nat server".to_string();
+    command.push_str(&protocol_part(entry));
+    command.push_str(&format!(
+        " global {}",
+        global_address(entry, TargetDevice::H3c)?
+    ));
+    command.push_str(&port_part(&entry.global_port));
+    command.push_str(&vpn_part(&entry.global_vpn_instance));
+    command.push_str(&format!(" inside {}", inside_address(entry)?));
+    command.push_str(&port_part(&entry.inside_port));
+    command.push_str(&vpn_part(&entry.vpn_instance));
+    if let Some(acl) = &entry.acl {
+        command.push_str(&format!(" acl {}", acl));
+    }
+    // Comware 的 reversible 只能跟在 acl 之后
+    if entry.reversible && entry.acl.is_some() {
+        command.push_str(" reversible");
+    }
+    if let Some(vrrp) = entry.vrrp {
+        command.push_str(&format!(" vrrp {}", vrrp));
+    }
+    command.push_str(&format!(" description {}", server_label(entry)));
+
+    if let Some(zone) = &entry.zone {
+        dropped.push(format!("安全区域 {}（H3C 需在对应接口下配置）", zone));
+    }
+    push_dropped(entry, dropped, translation);
+    if entry.reversible && entry.acl.is_none() {
+        translation.warnings.push(NatTranslationIssue {
+            source: entry.command.clone(),
+            reason: "原配置允许内部主机主动使用公网地址访问，H3C 需配置 acl 后加 reversible 才能实现，已省略"
+                .to_string(),
+        });
+    }
+
+    Ok(match &entry.scope_interface {
+        Some(interface) => vec![
+            format!("interface {}", interface),
+            format!(" {}", command),
+            "quit".to_string(),
+        ],
+        None => {
+            translation.warnings.push(NatTranslationIssue {
+                source: entry.command.clone(),
+                reason: "未知所在接口，H3C 需进入对应接口视图后下发该命令".to_string(),
+            });
+            vec![command]
+        }
+    })
+}
+
+fn push_dropped(entry: &NatEntry, dropped: Vec<String>, translation: &mut NatTranslationResult) {
+    if !dropped.is_empty() {
+        translation.warnings.push(NatTranslationIssue {
+            source: entry.command.clone(),
+            reason: format!("以下属性无法对应，已忽略：{}", dropped.join("、")),
+        });
+    }
+}
+
+// 华为名称与 H3C 描述：优先使用描述；H3C 未命名条目的 name 为 "-"，
+// 解析时生成的 "a:b -> c:d"、ASA 的 "REAL -> MAPPED" 也不适合使用
+fn server_label(entry: &NatEntry) -> String {
+    if let Some(description) = present(&entry.description) {
+        return description.to_string();
+    }
+    let name = entry.name.trim();
+    if !name.is_empty() && name != "-" && !name.contains(':') && !name.contains("->") {
+        return name.to_string();
+    }
+    let protocol = entry.protocol.to_string().to_uppercase();
+    match entry.inside_port {
+        None => format!("{}{}", protocol, entry.inside_ip),
+        Some(_) => format!(
+            "{}{}:{}",
+            protocol,
+            entry.inside_ip,
+            format_port(&entry.inside_port)
+        ),
+    }
+}
+
+pub(crate) fn quote_name(name: &str) -> String {
+    if name.contains(char::is_whitespace) {
+        format!("\"{}\"", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn present(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty() && *value != "-")
+}
+
+pub(crate) fn protocol_part(entry: &NatEntry) -> String {
+    match entry.protocol {
+        NatProtocol::Any => String::new(),
+        protocol => format!(" protocol {}", protocol),
+    }
+}
+
+pub(crate) fn port_part(port: &Option<PortRange>) -> String {
+    match port {
+        Some(range) if range.is_range() => format!(" {} {}", range.start, range.end),
+        Some(range) => format!(" {}", range.start),
+        None => String::new(),
+    }
+}
+
+pub(crate) fn vpn_part(vpn_instance: &Option<String>) -> String {
+    match vpn_instance {
+        Some(name) => format!(" vpn-instance {}", name),
+        None => String::new(),
+    }
+}
+
+// 接口地址映射：华为写 global interface X，H3C 写 global current-interface 或 interface X；
+// 华为没有 current-interface，改写为所在接口
+pub(crate) fn global_address(entry: &NatEntry, target: TargetDevice) -> Result<String, String> {
+    if entry.global_ip == NatAddress::Interface {
+        return match (&entry.interface, &entry.scope_interface, target) {
+            (Some(interface), _, _) => Ok(format!("interface {}", interface)),
+            (None, _, TargetDevice::H3c) => Ok("current-interface".to_string()),
+            (None, Some(scope), TargetDevice::Huawei) => Ok(format!("interface {}", scope)),
+            (None, None, TargetDevice::Huawei) => Err("使用接口地址映射但缺少接口名称".to_string()),
+        };
+    }
+    plain_ipv4(&entry.global_ip)
+}
+
+pub(crate) fn inside_address(entry: &NatEntry) -> Result<String, String> {
+    plain_ipv4(&entry.inside_ip)
+}
+
+fn plain_ipv4(address: &NatAddress) -> Result<String, String> {
+    match address {
+        NatAddress::Ip(IpAddr::V4(ip)) => Ok(ip.to_string()),
+        other => Err(format!(
+            "{} 不是单个 IPv4 地址，地址段/网段映射需手工转换",
+            other
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nat_parser::NATParser;
+
+    #[test]
+    fn translates_between_huawei_and_h3c() {
+        let huawei = "nat server web zone untrust protocol tcp global 203.0.113.1 8000 8010 inside 10.0.0.5 8000 8010 vrrp 1 no-reverse";
+        let parsed = NATParser::parse_config(huawei, "huawei");
+        let translated = translate_nat_config(parsed, TargetDevice::H3c).unwrap();
+        assert_eq!(
+            translated.commands,
+            vec!["nat server protocol tcp global 203.0.113.1 8000 8010 inside 10.0.0.5 8000 8010 vrrp 1 description web"]
+        );
+        assert_eq!(translated.warnings.len(), 2);
+
+        let h3c = "nat server protocol udp global 203.0.113.2 53 inside 10.0.0.6 53 vrrp 2 description dns server counting";
+        let parsed = NATParser::parse_config(h3c, "h3c");
+        let translated = translate_nat_config(parsed, TargetDevice::Huawei).unwrap();
+        assert_eq!(
+            translated.commands,
+            vec!["nat server \"dns server\" protocol udp global 203.0.113.2 53 inside 10.0.0.6 53 vrrp 2 no-reverse"]
+        );
+        assert!(translated.warnings[0].reason.contains("counting"));
+    }
+
+    #[test]
+    fn translates_h3c_context_and_address_groups() {
+        let huawei = "nat address-group 2\n section 0 202.1.1.1 202.1.1.2\n\
+                      nat address-group snat_pool 0\n mode pat\n section 0 202.1.1.10 202.1.1.15\n\
+                      nat server web protocol tcp global 203.0.113.1 80 inside 10.0.0.5 80\n\
+                      nat server ssh protocol tcp global 203.0.113.1 22 inside 10.0.0.6 22 acl 3000";
+        let parsed = NATParser::parse_config(huawei, "huawei");
+        let translated = translate_nat_config(parsed, TargetDevice::H3c).unwrap();
+        assert_eq!(
+            translated.commands,
+            vec![
+                "nat address-group 2",
+                " address 202.1.1.1 202.1.1.2",
+                "nat address-group 1 name snat_pool",
+                " address 202.1.1.10 202.1.1.15",
+                "nat server protocol tcp global 203.0.113.1 80 inside 10.0.0.5 80 description web",
+                "nat server protocol tcp global 203.0.113.1 22 inside 10.0.0.6 22 acl 3000 reversible description ssh",
+            ]
+        );
+        assert!(translated
+            .warnings
+            .iter()
+            .any(|issue| issue.reason.contains("acl") && issue.source.contains("web")));
+
+        let h3c = "interface GigabitEthernet1/0/1\n\
+                   nat server protocol tcp global 203.0.113.2 443 inside 10.0.0.7 443";
+        let parsed = NATParser::parse_config(h3c, "h3c");
+        let mut parsed_mixed = NATParser::parse_config(h3c, "h3c");
+        let translated = translate_nat_config(parsed, TargetDevice::Huawei).unwrap();
+        assert_eq!(translated.commands.len(), 1);
+
+        parsed_mixed.device_type = "mixed".to_string();
+        assert!(translate_nat_config(parsed_mixed, TargetDevice::Huawei).is_err());
+
+        let forti = "config firewall ippool\n\
+                     edit \"pool\"\n\
+                     set type overload\n\
+                     set startip 198.51.100.1\n\
+                     set endip 198.51.100.4\n\
+                     next\n\
+                     end";
+        let parsed = NATParser::parse_config(forti, "fortigate");
+        let translated = translate_nat_config(parsed, TargetDevice::Huawei).unwrap();
+        assert_eq!(
+            translated.commands,
+            vec![
+                "nat address-group pool",
+                " mode pat",
+                " section 0 198.51.100.1 198.51.100.4",
+            ]
+        );
+
+        let forti = "config firewall vip\n\
+                     edit \"web_vip\"\n\
+                     set extip 203.0.113.10\n\
+                     set mappedip \"10.1.1.10\"\n\
+                     set extintf \"port1\"\n\
+                     set portforward enable\n\
+                     set extport 443\n\
+                     set mappedport 8443\n\
+                     next\n\
+                     end";
+        let parsed = NATParser::parse_config(forti, "fortigate");
+        let translated = translate_nat_config(parsed, TargetDevice::H3c).unwrap();
+        assert_eq!(
+            translated.commands,
+            vec![
+                "interface port1",
+                " nat server protocol tcp global 203.0.113.10 443 inside 10.1.1.10 8443 description web_vip",
+                "quit",
+            ]
+        );
+    }
+
+    #[test]
+    fn labels_h3c_description_without_generated_names() {
+        let ios = "ip nat inside source static tcp 10.1.1.10 80 203.0.113.9 8080";
+        let parsed = NATParser::parse_config(ios, "cisco-ios");
+        assert!(parsed.success_entries[0].name.contains("->"));
+        let translated = translate_nat_config(parsed, TargetDevice::H3c).unwrap();
+        assert_eq!(
+            translated.commands,
+            vec!["nat server protocol tcp global 203.0.113.9 8080 inside 10.1.1.10 80 description TCP10.1.1.10:80"]
+        );
+    }
+}
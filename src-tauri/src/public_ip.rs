@@ -0,0 +1,478 @@
+use crate::isp_manager::{config_dir, detect_isp_info, read_text_file, write_atomic};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+const SERVICES_FILENAME: &str = "public_ip_services.yaml";
+const DEFAULT_TIMEOUT_SECS: u64 = 6;
+const MAX_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceFamily {
+    Ipv4,
+    Ipv6,
+    Dual,
+}
+
+// 响应解析方式：纯文本、JSON Pointer（如 /data/ip）或正则（优先取第一个捕获组）
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ResponseFormat {
+    Plain,
+    JsonPointer { pointer: String },
+    Regex { pattern: String },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicIpService {
+    pub name: String,
+    pub url: String,
+    pub format: ResponseFormat,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    pub family: ServiceFamily,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+// 双栈服务会分别通过 IPv4 和 IPv6 各请求一次
+fn default_services() -> Vec<PublicIpService> {
+    let service =
+        |name: &str, url: &str, format: ResponseFormat, family: ServiceFamily| PublicIpService {
+            name: name.to_string(),
+            url: url.to_string(),
+            format,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            family,
+            enabled: true,
+        };
+
+    vec![
+        // ipip.net 返回 "当前 IP：111.205.145.129  来自于：中国 北京 北京  联通"
+        service(
+            "ipip.net",
+            "https://myip.ipip.net/",
+            ResponseFormat::Regex {
+                pattern: r"(\d{1,3}(?:\.\d{1,3}){3})".to_string(),
+            },
+            ServiceFamily::Ipv4,
+        ),
+        service(
+            "ip.sb",
+            "https://api.ip.sb/ip",
+            ResponseFormat::Plain,
+            ServiceFamily::Dual,
+        ),
+        service(
+            "ipify",
+            "https://api64.ipify.org",
+            ResponseFormat::Plain,
+            ServiceFamily::Dual,
+        ),
+        service(
+            "icanhazip",
+            "https://icanhazip.com",
+            ResponseFormat::Plain,
+            ServiceFamily::Dual,
+        ),
+        service(
+            "ipecho",
+            "https://ipecho.net/plain",
+            ResponseFormat::Plain,
+            ServiceFamily::Ipv4,
+        ),
+        service(
+            "amazonaws",
+            "https://checkip.amazonaws.com",
+            ResponseFormat::Plain,
+            ServiceFamily::Ipv4,
+        ),
+        service(
+            "ident.me",
+            "https://v6.ident.me",
+            ResponseFormat::Plain,
+            ServiceFamily::Ipv6,
+        ),
+    ]
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicIpObservation {
+    pub service: String,
+    pub url: String,
+    pub family: String,
+    pub ip: Option<String>,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EgressAddress {
+    pub ip: String,
+    pub services: Vec<String>,
+    pub isp: Option<String>,
+    pub matched_range: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FamilyReport {
+    pub reachable: bool,
+    pub consistent: bool,
+    pub addresses: Vec<EgressAddress>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicIpReport {
+    pub ipv4: FamilyReport,
+    pub ipv6: FamilyReport,
+    pub observations: Vec<PublicIpObservation>,
+    pub warnings: Vec<String>,
+}
+
+#[tauri::command]
+pub fn get_public_ip_services() -> Result<Vec<PublicIpService>, String> {
+    load_services()
+}
+
+#[tauri::command]
+pub fn update_public_ip_services(
+    services: Vec<PublicIpService>,
+) -> Result<Vec<PublicIpService>, String> {
+    validate_services(&services)?;
+    let serialized = serde_yaml::to_string(&services)
+        .map_err(|err| format!("序列化公网 IP 服务列表失败: {err}"))?;
+    write_atomic(&config_dir()?.join(SERVICES_FILENAME), &serialized)?;
+    Ok(services)
+}
+
+#[tauri::command]
+pub fn reset_public_ip_services() -> Result<Vec<PublicIpService>, String> {
+    update_public_ip_services(default_services())
+}
+
+#[tauri::command]
+pub async fn get_public_ip_report() -> Result<PublicIpReport, String> {
+    let services = load_services()?;
+    let mut tasks = JoinSet::new();
+    for (index, service) in services.into_iter().filter(|item| item.enabled).enumerate() {
+        let families: &[bool] = match service.family {
+            ServiceFamily::Ipv4 => &[true],
+            ServiceFamily::Ipv6 => &[false],
+            ServiceFamily::Dual => &[true, false],
+        };
+        for &use_ipv4 in families {
+            let service = service.clone();
+            tasks.spawn(async move {
+                let observation = query_service(&service, use_ipv4).await;
+                (index, use_ipv4, observation)
+            });
+        }
+    }
+    if tasks.is_empty() {
+        return Err("没有启用的公网 IP 服务".to_string());
+    }
+
+    let mut collected = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        collected.push(joined.map_err(|err| format!("查询任务异常: {err}"))?);
+    }
+    // 按服务列表顺序输出，先 IPv4 后 IPv6
+    collected.sort_by_key(|(index, use_ipv4, _)| (*index, !*use_ipv4));
+    let observations: Vec<PublicIpObservation> = collected
+        .into_iter()
+        .map(|(_, _, observation)| observation)
+        .collect();
+
+    let mut warnings = Vec::new();
+    let ipv4 = build_family_report(&observations, "IPv4", &mut warnings);
+    let ipv6 = build_family_report(&observations, "IPv6", &mut warnings);
+
+    if !ipv4.reachable && !ipv6.reachable {
+        return Err("无法获取公网 IP 地址".to_string());
+    }
+
+    Ok(PublicIpReport {
+        ipv4,
+        ipv6,
+        observations,
+        warnings,
+    })
+}
+
+// 按列表顺序逐个尝试，返回第一个拿到的 IPv4 出口地址
+pub(crate) async fn first_public_ipv4() -> Result<String, String> {
+    let services = load_services()?;
+    for service in services
+        .iter()
+        .filter(|item| item.enabled && item.family != ServiceFamily::Ipv6)
+    {
+        if let Some(ip) = query_service(service, true).await.ip {
+            return Ok(ip);
+        }
+    }
+    Err("无法获取公网 IP 地址".to_string())
+}
+
+fn load_services() -> Result<Vec<PublicIpService>, String> {
+    let path = config_dir()?.join(SERVICES_FILENAME);
+    match read_text_file(&path)? {
+        Some(content) if !content.trim().is_empty() => {
+            serde_yaml::from_str(&content).map_err(|err| format!("解析公网 IP 服务列表失败: {err}"))
+        }
+        _ => Ok(default_services()),
+    }
+}
+
+fn validate_services(services: &[PublicIpService]) -> Result<(), String> {
+    if services.is_empty() {
+        return Err("公网 IP 服务列表不能为空".to_string());
+    }
+
+    let mut names = HashSet::new();
+    for service in services {
+        let name = service.name.trim();
+        if name.is_empty() {
+            return Err("服务名称不能为空".to_string());
+        }
+        if !names.insert(name.to_string()) {
+            return Err(format!("服务名称重复: {}", name));
+        }
+        let url = reqwest::Url::parse(service.url.trim())
+            .map_err(|err| format!("{} 的 URL 无效: {}", name, err))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("{} 的 URL 必须以 http 或 https 开头", name));
+        }
+        if service.timeout_secs == 0 || service.timeout_secs > MAX_TIMEOUT_SECS {
+            return Err(format!(
+                "{} 的超时时间必须在 1 到 {} 秒之间",
+                name, MAX_TIMEOUT_SECS
+            ));
+        }
+        match &service.format {
+            ResponseFormat::Plain => {}
+            ResponseFormat::JsonPointer { pointer } => {
+                if !pointer.starts_with('/') {
+                    return Err(format!("{} 的 JSON Pointer 必须以 / 开头", name));
+                }
+            }
+            ResponseFormat::Regex { pattern } => {
+                Regex::new(pattern).map_err(|err| format!("{} 的正则表达式无效: {}", name, err))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// 通过绑定对应协议族的未指定地址，强制请求走 IPv4 或 IPv6
+async fn query_service(service: &PublicIpService, use_ipv4: bool) -> PublicIpObservation {
+    let family = if use_ipv4 { "IPv4" } else { "IPv6" };
+    let local_address = if use_ipv4 {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+    };
+    let started = Instant::now();
+
+    let result = async {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(service.timeout_secs.max(1)))
+            .local_address(local_address)
+            .build()
+            .map_err(|err| format!("创建请求失败: {}", err))?;
+        let text = client
+            .get(&service.url)
+            .send()
+            .await
+            .map_err(|err| format!("请求失败: {}", err))?
+            .text()
+            .await
+            .map_err(|err| format!("读取响应失败: {}", err))?;
+        parse_response(&text, &service.format, use_ipv4)?
+            .ok_or_else(|| format!("响应中没有有效的 {} 地址", family))
+    }
+    .await;
+
+    let (ip, error) = match result {
+        Ok(ip) => (Some(ip.to_string()), None),
+        Err(err) => (None, Some(err)),
+    };
+    PublicIpObservation {
+        service: service.name.clone(),
+        url: service.url.clone(),
+        family: family.to_string(),
+        ip,
+        error,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn parse_response(
+    text: &str,
+    format: &ResponseFormat,
+    use_ipv4: bool,
+) -> Result<Option<IpAddr>, String> {
+    let candidate = match format {
+        ResponseFormat::Plain => text.trim().to_string(),
+        ResponseFormat::JsonPointer { pointer } => {
+            let json: Value = serde_json::from_str(text)
+                .map_err(|err| format!("响应不是有效的 JSON: {}", err))?;
+            match json.pointer(pointer) {
+                Some(Value::String(value)) => value.trim().to_string(),
+                Some(_) | None => return Ok(None),
+            }
+        }
+        ResponseFormat::Regex { pattern } => {
+            let re = Regex::new(pattern).map_err(|err| format!("正则表达式无效: {}", err))?;
+            // 逐个匹配，跳过协议族不符的地址
+            return Ok(re
+                .captures_iter(text)
+                .filter_map(|caps| caps.get(1).or_else(|| caps.get(0)))
+                .filter_map(|found| found.as_str().trim().parse::<IpAddr>().ok())
+                .find(|ip| ip.is_ipv4() == use_ipv4));
+        }
+    };
+
+    Ok(candidate
+        .parse::<IpAddr>()
+        .ok()
+        .filter(|ip| ip.is_ipv4() == use_ipv4))
+}
+
+// 同一协议族出现多个出口地址时，说明存在策略路由、负载均衡或多出口
+fn build_family_report(
+    observations: &[PublicIpObservation],
+    family: &str,
+    warnings: &mut Vec<String>,
+) -> FamilyReport {
+    let mut addresses: Vec<EgressAddress> = Vec::new();
+    for observation in observations.iter().filter(|item| item.family == family) {
+        let Some(ip) = &observation.ip else {
+            continue;
+        };
+        match addresses.iter_mut().find(|address| &address.ip == ip) {
+            Some(address) => address.services.push(observation.service.clone()),
+            None => {
+                let detected = if family == "IPv4" {
+                    detect_isp_info(ip.clone()).ok()
+                } else {
+                    None
+                };
+                addresses.push(EgressAddress {
+                    ip: ip.clone(),
+                    services: vec![observation.service.clone()],
+                    isp: detected.as_ref().and_then(|info| info.isp.clone()),
+                    matched_range: detected.and_then(|info| info.matched_range),
+                });
+            }
+        }
+    }
+
+    if addresses.len() > 1 {
+        let details: Vec<String> = addresses
+            .iter()
+            .map(|address| format!("{}（{}）", address.ip, address.services.join("、")))
+            .collect();
+        warnings.push(format!(
+            "{} 出口地址不一致：{}，可能存在策略路由、负载均衡或多条出口线路",
+            family,
+            details.join("；")
+        ));
+    }
+
+    FamilyReport {
+        reachable: !addresses.is_empty(),
+        consistent: addresses.len() <= 1,
+        addresses,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(service: &str, family: &str, ip: Option<&str>) -> PublicIpObservation {
+        PublicIpObservation {
+            service: service.to_string(),
+            url: String::new(),
+            family: family.to_string(),
+            ip: ip.map(str::to_string),
+            error: ip.is_none().then(|| "超时".to_string()),
+            elapsed_ms: 0,
+        }
+    }
+
+    #[test]
+    fn flags_disagreeing_egress_addresses() {
+        let observations = vec![
+            observation("ip.sb", "IPv6", Some("2001:db8::1")),
+            observation("ipify", "IPv6", Some("2001:db8::1")),
+            observation("ipify", "IPv6", None),
+            observation("ip.sb", "IPv6", Some("2001:db8::2")),
+        ];
+        let mut warnings = Vec::new();
+        let report = build_family_report(&observations, "IPv6", &mut warnings);
+
+        assert!(!report.consistent);
+        assert_eq!(report.addresses.len(), 2);
+        assert_eq!(report.addresses[0].services, vec!["ip.sb", "ipify"]);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn parses_service_responses_by_format() {
+        let ipip = &default_services()[0].format;
+        let text = "当前 IP：111.205.145.129  来自于：中国 北京 北京  联通";
+        assert_eq!(
+            parse_response(text, ipip, true).unwrap(),
+            Some("111.205.145.129".parse().unwrap())
+        );
+
+        let pointer = ResponseFormat::JsonPointer {
+            pointer: "/data/ip".to_string(),
+        };
+        let json = r#"{"data":{"ip":"2001:db8::8"}}"#;
+        assert!(parse_response(json, &pointer, true).unwrap().is_none());
+        assert_eq!(
+            parse_response(json, &pointer, false).unwrap(),
+            Some("2001:db8::8".parse().unwrap())
+        );
+        assert!(
+            parse_response("2001:db8::1\n", &ResponseFormat::Plain, false)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_service_definitions() {
+        let mut services = default_services();
+        assert!(validate_services(&services).is_ok());
+
+        services[1].format = ResponseFormat::Regex {
+            pattern: "(".to_string(),
+        };
+        assert!(validate_services(&services).is_err());
+
+        let mut services = default_services();
+        services[1].url = "ftp://example.com".to_string();
+        assert!(validate_services(&services).is_err());
+    }
+}
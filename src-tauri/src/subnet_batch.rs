@@ -0,0 +1,365 @@
+use crate::ipv4_subnet::{build_subnet_result, parse_subnet_input};
+use crate::ipv6_address::describe_ipv6;
+use crate::ipv6_subnet::format_biguint;
+use calamine::{open_workbook_auto, Data, Range, Reader};
+use num_bigint::BigUint;
+use num_traits::One;
+use rust_xlsxwriter::{Format, FormatAlign, Workbook};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv6Addr;
+
+const MAX_BATCH_ROWS: usize = 10000;
+const EXPORT_HEADERS: [&str; 12] = [
+    "序号",
+    "输入",
+    "协议族",
+    "网络地址",
+    "子网掩码",
+    "反掩码",
+    "广播地址",
+    "可用范围",
+    "可用主机数",
+    "地址类型",
+    "输入解析",
+    "错误",
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubnetBatchRow {
+    pub index: usize,
+    pub input: String,
+    pub family: String,
+    pub network: String,
+    pub subnet_mask: String,
+    pub wildcard_mask: String,
+    pub broadcast: String,
+    pub usable_range: String,
+    pub usable_hosts: String,
+    pub ip_type: String,
+    pub interpretation: String,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubnetBatchResponse {
+    pub rows: Vec<SubnetBatchRow>,
+    pub success_count: usize,
+    pub error_count: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "source", rename_all = "camelCase")]
+pub enum SubnetBatchRequest {
+    Text {
+        lines: Vec<String>,
+    },
+    Excel {
+        file_path: String,
+        sheet_name: Option<String>,
+        column: String,
+    },
+}
+
+#[tauri::command]
+pub fn compute_subnets_batch(request: SubnetBatchRequest) -> Result<SubnetBatchResponse, String> {
+    let inputs = match request {
+        SubnetBatchRequest::Text { lines } => lines,
+        SubnetBatchRequest::Excel {
+            file_path,
+            sheet_name,
+            column,
+        } => read_excel_column(&file_path, sheet_name, &column)?,
+    };
+
+    let inputs: Vec<String> = inputs
+        .into_iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+
+    if inputs.is_empty() {
+        return Err("没有可计算的地址".to_string());
+    }
+    if inputs.len() > MAX_BATCH_ROWS {
+        return Err(format!(
+            "一次最多计算 {} 条，当前 {} 条",
+            MAX_BATCH_ROWS,
+            inputs.len()
+        ));
+    }
+
+    let rows: Vec<SubnetBatchRow> = inputs
+        .iter()
+        .enumerate()
+        .map(|(idx, input)| compute_row(idx + 1, input))
+        .collect();
+
+    let error_count = rows.iter().filter(|row| row.error.is_some()).count();
+    Ok(SubnetBatchResponse {
+        success_count: rows.len() - error_count,
+        error_count,
+        rows,
+    })
+}
+
+#[tauri::command]
+pub fn export_subnet_batch(path: String, rows: Vec<SubnetBatchRow>) -> Result<(), String> {
+    if rows.is_empty() {
+        return Err("没有可导出的计算结果".into());
+    }
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let header_format = Format::new().set_bold().set_align(FormatAlign::Center);
+
+    for (col, header) in EXPORT_HEADERS.iter().enumerate() {
+        worksheet
+            .set_column_width(col as u16, 18.0)
+            .map_err(|err| format!("设置列宽失败: {err}"))?;
+        worksheet
+            .write_string_with_format(0, col as u16, *header, &header_format)
+            .map_err(|err| format!("写入表头失败: {err}"))?;
+    }
+
+    for (row_idx, row) in rows.iter().enumerate() {
+        let values = [
+            row.index.to_string(),
+            row.input.clone(),
+            row.family.clone(),
+            row.network.clone(),
+            row.subnet_mask.clone(),
+            row.wildcard_mask.clone(),
+            row.broadcast.clone(),
+            row.usable_range.clone(),
+            row.usable_hosts.clone(),
+            row.ip_type.clone(),
+            row.interpretation.clone(),
+            row.error.clone().unwrap_or_default(),
+        ];
+        for (col_idx, value) in values.iter().enumerate() {
+            worksheet
+                .write_string((row_idx + 1) as u32, col_idx as u16, value)
+                .map_err(|err| format!("写入计算结果失败: {err}"))?;
+        }
+    }
+
+    workbook
+        .save(path)
+        .map_err(|err| format!("保存文件失败: {err}"))?;
+    Ok(())
+}
+
+fn compute_row(index: usize, input: &str) -> SubnetBatchRow {
+    let computed = if input.contains(':') {
+        compute_ipv6_row(index, input)
+    } else {
+        compute_ipv4_row(index, input)
+    };
+
+    computed.unwrap_or_else(|err| SubnetBatchRow {
+        index,
+        input: input.to_string(),
+        family: if input.contains(':') { "IPv6" } else { "IPv4" }.to_string(),
+        network: String::new(),
+        subnet_mask: String::new(),
+        wildcard_mask: String::new(),
+        broadcast: String::new(),
+        usable_range: String::new(),
+        usable_hosts: String::new(),
+        ip_type: String::new(),
+        interpretation: String::new(),
+        error: Some(err),
+    })
+}
+
+fn compute_ipv4_row(index: usize, input: &str) -> Result<SubnetBatchRow, String> {
+    let (ip_addr, cidr, interpretation) = parse_subnet_input(input)?;
+    let result = build_subnet_result(ip_addr, cidr);
+
+    Ok(SubnetBatchRow {
+        index,
+        input: input.to_string(),
+        family: "IPv4".to_string(),
+        network: format!("{}{}", result.network_address, result.cidr_notation),
+        subnet_mask: result.subnet_mask,
+        wildcard_mask: result.wildcard_mask,
+        broadcast: result.broadcast_address,
+        usable_range: result.usable_range,
+        usable_hosts: result.usable_hosts,
+        ip_type: result.ip_type,
+        interpretation: interpretation.description,
+        error: None,
+    })
+}
+
+// IPv6 只计算网段边界，避免像 compute_ipv6_subnet 那样逐个枚举地址
+fn compute_ipv6_row(index: usize, input: &str) -> Result<SubnetBatchRow, String> {
+    let (ip_part, cidr) = match input.split_once('/') {
+        Some((ip, cidr_part)) => {
+            let cidr: u32 = cidr_part
+                .trim()
+                .parse()
+                .map_err(|_| "CIDR 必须是 0 到 128 的整数".to_string())?;
+            if cidr > 128 {
+                return Err("CIDR 必须在 0 到 128 之间".to_string());
+            }
+            (ip.trim(), cidr)
+        }
+        None => (input, 128),
+    };
+
+    let addr: Ipv6Addr = ip_part
+        .parse()
+        .map_err(|_| "IPv6 地址格式不正确".to_string())?;
+
+    let mask = if cidr == 0 {
+        0
+    } else {
+        u128::MAX << (128 - cidr)
+    };
+    let network = u128::from(addr) & mask;
+    let last = network | !mask;
+    let total = BigUint::one() << (128 - cidr);
+
+    Ok(SubnetBatchRow {
+        index,
+        input: input.to_string(),
+        family: "IPv6".to_string(),
+        network: format!("{}/{}", Ipv6Addr::from(network), cidr),
+        subnet_mask: Ipv6Addr::from(mask).to_string(),
+        wildcard_mask: Ipv6Addr::from(!mask).to_string(),
+        broadcast: "-".to_string(),
+        usable_range: format!("{} - {}", Ipv6Addr::from(network), Ipv6Addr::from(last)),
+        usable_hosts: format_biguint(&total),
+        ip_type: describe_ipv6(&addr).address_type,
+        interpretation: if input.contains('/') {
+            format!("CIDR /{}", cidr)
+        } else {
+            "未指定前缀，按单个地址 /128 计算".to_string()
+        },
+        error: None,
+    })
+}
+
+pub(crate) fn read_excel_column(
+    file_path: &str,
+    sheet_name: Option<String>,
+    column: &str,
+) -> Result<Vec<String>, String> {
+    let mut workbook =
+        open_workbook_auto(file_path).map_err(|err| format!("无法打开 Excel 文件: {err}"))?;
+
+    let sheet_names = workbook.sheet_names().to_vec();
+    if sheet_names.is_empty() {
+        return Err("Excel 文件中没有工作表".to_string());
+    }
+    // 未指定时取第一个工作表；指定了但不存在时报错，避免误读其他工作表
+    let selected = match sheet_name.map(|name| name.trim().to_string()) {
+        Some(name) if !name.is_empty() => {
+            if !sheet_names.contains(&name) {
+                return Err(format!(
+                    "工作表 \"{}\" 不存在，可选工作表：{}",
+                    name,
+                    sheet_names.join("、")
+                ));
+            }
+            name
+        }
+        _ => sheet_names[0].clone(),
+    };
+
+    let range = workbook
+        .worksheet_range(&selected)
+        .map_err(|err| format!("读取工作表失败: {err}"))?;
+
+    let (header_row_index, column_index) = find_column(&range, column)
+        .ok_or_else(|| format!("无法在表头中找到列 \"{}\"", column.trim()))?;
+
+    Ok(range
+        .rows()
+        .skip(header_row_index + 1)
+        .filter_map(|row| row.get(column_index))
+        .map(data_type_to_string)
+        .collect())
+}
+
+// 以第一个非空行作为表头，定位目标列
+fn find_column(range: &Range<Data>, column: &str) -> Option<(usize, usize)> {
+    let target = column.trim().to_lowercase();
+    let (header_row_index, header) = range
+        .rows()
+        .enumerate()
+        .find(|(_, row)| row.iter().any(|cell| !data_type_to_string(cell).is_empty()))?;
+
+    header
+        .iter()
+        .position(|cell| data_type_to_string(cell).to_lowercase() == target)
+        .map(|column_index| (header_row_index, column_index))
+}
+
+fn data_type_to_string(value: &Data) -> String {
+    match value {
+        Data::String(s) => s.trim().to_string(),
+        Data::Float(v) => {
+            if (v.fract()).abs() < f64::EPSILON {
+                format!("{:.0}", v)
+            } else {
+                v.to_string()
+            }
+        }
+        Data::Int(v) => v.to_string(),
+        Data::Bool(v) => v.to_string(),
+        Data::Empty | Data::Error(_) => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_ipv6_row_boundaries() {
+        let row = compute_ipv6_row(1, "2001:db8::1/126").expect("should compute");
+        assert_eq!(row.network, "2001:db8::/126");
+        assert_eq!(row.usable_range, "2001:db8:: - 2001:db8::3");
+        assert_eq!(row.usable_hosts, "4");
+
+        let single = compute_ipv6_row(2, "2001:db8::1").expect("should compute");
+        assert_eq!(single.network, "2001:db8::1/128");
+        assert!(compute_ipv6_row(3, "2001:db8::/129").is_err());
+    }
+
+    #[test]
+    fn converts_cells_to_text() {
+        assert_eq!(data_type_to_string(&Data::Float(24.0)), "24");
+        assert_eq!(data_type_to_string(&Data::Float(1.5)), "1.5");
+        assert_eq!(
+            data_type_to_string(&Data::String(" 10.0.0.0/8 ".into())),
+            "10.0.0.0/8"
+        );
+        assert_eq!(data_type_to_string(&Data::Empty), "");
+    }
+
+    #[test]
+    fn rejects_unknown_sheet_name() {
+        let file = tempfile::Builder::new()
+            .suffix(".xlsx")
+            .tempfile()
+            .expect("temp file");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name("网段").unwrap();
+        worksheet.write_string(0, 0, "地址").unwrap();
+        worksheet.write_string(1, 0, "10.0.0.0/24").unwrap();
+        workbook.save(file.path()).unwrap();
+        let path = file.path().to_string_lossy().to_string();
+
+        let values = read_excel_column(&path, Some("网段".into()), "地址").unwrap();
+        assert_eq!(values, vec!["10.0.0.0/24"]);
+        let err = read_excel_column(&path, Some("Sheet2".into()), "地址").unwrap_err();
+        assert!(err.contains("Sheet2"));
+    }
+}
@@ -37,8 +37,15 @@ export interface GenerateNatCommandsRequest {
 	ispSource?: IspSource
 }
 
+export interface NatTranslationIssue {
+	source: string
+	reason: string
+}
+
 export interface GenerateNatCommandsResponse {
 	commands: string[]
+	rollbackCommands: string[]
+	rollbackSkipped: NatTranslationIssue[]
 	missingElasticIps: string[]
 }
 